@@ -0,0 +1,99 @@
+//! Cache of the ABIs read for each .sol file (`--cache`), keyed on the hash of the file's and its imports' content.
+//! The ABIs are cached rather than the computed rows, so the other options can change between runs.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use log::{debug, warn};
+use selector_calculator::AbiEntry;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+/// Cache file, in the current directory.
+pub const CACHE_FILE: &str = ".selector_cache.json";
+
+/// ABI of each contract defined in a file, as `(contract name, ABI)`.
+pub type FileAbis = Vec<(String, Vec<AbiEntry>)>;
+
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+    hash: String,
+    contracts: FileAbis,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Cache {
+    files: BTreeMap<PathBuf, CachedFile>,
+}
+
+impl Cache {
+    /// Read the cache file, starting with an empty cache if it's missing or invalid.
+    pub fn load() -> Self {
+        let Ok(content) = std::fs::read_to_string(CACHE_FILE) else {
+            debug!("No cache file {:?}", CACHE_FILE);
+            return Cache::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|err| {
+            warn!("Ignoring invalid cache file {:?}: {}", CACHE_FILE, err);
+            Cache::default()
+        })
+    }
+
+    /// Cached ABIs of `path`'s contracts, if its content hasn't changed since.
+    pub fn get(&self, path: &Path, hash: &str) -> Option<&FileAbis> {
+        self.files.get(path).filter(|file| file.hash == hash).map(|file| &file.contracts)
+    }
+
+    pub fn insert(&mut self, path: &Path, hash: String, contracts: FileAbis) {
+        self.files.insert(path.to_path_buf(), CachedFile { hash, contracts });
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = serde_json::to_string(self).context("Error serializing the cache")?;
+        std::fs::write(CACHE_FILE, content).with_context(|| format!("Error writting on {:?}", CACHE_FILE))
+    }
+}
+
+/// Hash of a file's content and of the files it imports, recursively, `None` if it can't be read.
+/// A change in a parent contract defined elsewhere changes its children's hash too.
+/// Imports are resolved from the importing file's folder (`./`, `../`) or the current directory, the ones that
+/// don't resolve are left out: a change in a remapped import (e.g. `@openzeppelin/...`) doesn't invalidate the
+/// cache, delete `.selector_cache.json` then.
+pub fn content_hash(path: &Path) -> Option<String> {
+    let mut hasher = Keccak256::new();
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![std::fs::canonicalize(path).ok()?];
+    while let Some(file) = pending.pop() {
+        if !seen.insert(file.clone()) {
+            continue;
+        }
+        let content = std::fs::read(&file).ok()?;
+        hasher.update(&content);
+        // Vyper's imports are modules, not paths
+        if file.extension().is_some_and(|extension| extension != "sol") {
+            continue;
+        }
+        for import in imports(&String::from_utf8_lossy(&content)) {
+            let base = if import.starts_with('.') { file.parent().unwrap_or(Path::new("")) } else { Path::new("") };
+            // Canonical, so a file imported through different relative paths is hashed once
+            pending.extend(std::fs::canonicalize(base.join(import)).ok().filter(|p| p.is_file()));
+        }
+    }
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Paths of a Solidity file's `import` statements, e.g. `./Base.sol` for `import {Base} from "./Base.sol";`.
+fn imports(source: &str) -> Vec<&str> {
+    source
+        .split(';')
+        .filter_map(|statement| {
+            // Comments and other statements can come first on the previous lines
+            let start = statement.lines().map(str::trim_start).find(|line| line.starts_with("import"))?;
+            let start = &statement[statement.find(start)?..];
+            let quote = start.rfind(['"', '\''])?;
+            let open = start[..quote].rfind(['"', '\''])?;
+            Some(&start[open + 1..quote])
+        })
+        .collect()
+}
@@ -0,0 +1,36 @@
+//! Default options read from a `selector_calculator.toml` file.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::OutputFormat;
+
+/// Config file looked for in the current directory when `--config` isn't given.
+pub const CONFIG_FILE: &str = "selector_calculator.toml";
+
+/// Defaults for the options, overridden by the ones given in the command line.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub output_dir: Option<String>,
+    pub format: Option<OutputFormat>,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub dedup: bool,
+}
+
+/// Read the config at `path`, or `selector_calculator.toml` if it exists (default config otherwise).
+pub fn load(path: Option<&str>) -> Result<Config, String> {
+    let path = match path {
+        Some(path) => Path::new(path),
+        None if Path::new(CONFIG_FILE).is_file() => Path::new(CONFIG_FILE),
+        None => return Ok(Config::default()),
+    };
+    let content =
+        std::fs::read_to_string(path).map_err(|err| format!("Config file {:?} couldn't be read: {}", path, err))?;
+    toml::from_str(&content).map_err(|err| format!("Invalid config file {:?}: {}", path, err))
+}
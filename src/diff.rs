@@ -0,0 +1,123 @@
+//! Comparison of the functions' selectors with a previous `selectors.csv` (`--diff`).
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+
+use crate::output::CsvRows;
+use crate::ContractData;
+
+/// A function as `(contract, selector, signature)`.
+type Entry = (String, String, String);
+
+/// Functions listed in a `selectors.csv` written by a previous run, grouped by contract or deduplicated,
+/// with the same `delimiter`.
+fn read_selectors(path: &Path, delimiter: u8) -> anyhow::Result<BTreeSet<Entry>> {
+    // Skipping the '# solc <version>' line of '--solc-comment'
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .comment(Some(b'#'))
+        .from_path(path)
+        .with_context(|| format!("File {:?} couldn't be read", path))?;
+    let header = reader.headers().with_context(|| format!("Error reading {:?}", path))?.clone();
+    let column = |name: &str| header.iter().position(|c| c == name);
+    let (Some(signature_col), Some(selector_col)) = (column("function"), column("selector")) else {
+        bail!("{:?} isn't a selectors.csv, it has no 'function' and 'selector' columns", path);
+    };
+    let contract_col = column("contractName");
+    let contracts_col = column("contracts");
+
+    let mut entries = BTreeSet::new();
+    // Grouped tables only give the contract's name on its first row
+    let mut contract = String::new();
+    for record in reader.records() {
+        let record = record.with_context(|| format!("Error reading {:?}", path))?;
+        let field = |i: usize| record.get(i).unwrap_or_default().to_string();
+        if let Some(name) = contract_col.map(field).filter(|name| !name.is_empty()) {
+            contract = name;
+        }
+        let signature = field(signature_col);
+        let selector = field(selector_col);
+        let selector = selector.strip_prefix("0x").unwrap_or(&selector).to_string();
+        // Name rows, and the comment rows of '--group-by-mutability'. Functions of '--from-bytecode' only have
+        // a selector
+        if (signature.is_empty() && selector.is_empty()) || signature.starts_with('#') {
+            continue;
+        }
+        match contracts_col {
+            Some(i) => {
+                for contract in field(i).split(';') {
+                    entries.insert((contract.to_string(), selector.clone(), signature.clone()));
+                }
+            }
+            None => {
+                entries.insert((contract.clone(), selector, signature));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Differences between the old and the current functions, as CSV rows:
+/// `[change, contract, selector, signature, oldSignature]` where the change is `added`, `removed`,
+/// or `changed` for a selector kept by a different signature. Returns the number of removed functions too.
+/// With `only_processed`, the old functions of the contracts that weren't processed (e.g. left out by `--since`)
+/// aren't removed ones. Selectors are compared without their `0x` prefix, written by a run with `--no-0x` or not,
+/// and the rows have it unless `no_0x`.
+pub fn selectors_diff(
+    old_path: &Path,
+    contracts: &[ContractData],
+    only_processed: bool,
+    no_0x: bool,
+    delimiter: u8,
+) -> anyhow::Result<(CsvRows, usize)> {
+    let mut old = read_selectors(old_path, delimiter)?;
+    if only_processed {
+        old.retain(|(contract, _, _)| contracts.iter().any(|c| &c.name == contract));
+    }
+    // Without signature ('--from-bytecode'), a function is the old one with the same contract and selector
+    let new: BTreeSet<Entry> = contracts
+        .iter()
+        .flat_map(|c| &c.functions)
+        .map(|f| {
+            let selector = f.selector.strip_prefix("0x").unwrap_or(&f.selector).to_string();
+            let signature = old
+                .iter()
+                .find(|(c, s, _)| f.signature.is_empty() && *c == f.contract && *s == selector)
+                .map_or_else(|| f.signature.clone(), |(_, _, signature)| signature.clone());
+            (f.contract.clone(), selector, signature)
+        })
+        .collect();
+    let mut removed: Vec<&Entry> = old.difference(&new).collect();
+    let mut added: Vec<&Entry> = new.difference(&old).collect();
+
+    let prefixed = |selector: &str| match selector {
+        "" => String::new(),
+        _ if no_0x => selector.to_string(),
+        _ => format!("0x{}", selector),
+    };
+    let mut rows = vec![["change", "contract", "selector", "signature", "oldSignature"].map(str::to_string).to_vec()];
+    // Same contract and selector on both sides: the signature changed, the selector is still callable
+    removed.retain(|(contract, selector, old_signature)| {
+        let same_selector = added.iter().position(|(c, s, _)| c == contract && s == selector && !s.is_empty());
+        let Some(i) = same_selector else {
+            return true;
+        };
+        let (_, _, signature) = added.remove(i);
+        rows.push(vec![
+            "changed".to_string(),
+            contract.clone(),
+            prefixed(selector),
+            signature.clone(),
+            old_signature.clone(),
+        ]);
+        false
+    });
+    for (change, entries) in [("added", &added), ("removed", &removed)] {
+        for (contract, selector, signature) in entries.iter() {
+            rows.push(vec![change.to_string(), contract.clone(), prefixed(selector), signature.clone(), String::new()]);
+        }
+    }
+    Ok((rows, removed.len()))
+}
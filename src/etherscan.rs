@@ -0,0 +1,102 @@
+//! ABI of a verified on-chain contract, fetched from the Etherscan API (`--address`).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use log::{debug, info, warn};
+use selector_calculator::AbiEntry;
+use serde::{Deserialize, Serialize};
+
+const API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Folder, in the current directory, where the fetched ABIs are kept.
+const CACHE_DIR: &str = ".etherscan_cache";
+
+#[derive(Deserialize)]
+struct Response {
+    status: String,
+    // The contracts' source on success, the error message otherwise.
+    result: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct SourceCode {
+    #[serde(rename = "ABI")]
+    abi: String,
+    #[serde(rename = "ContractName")]
+    contract_name: String,
+}
+
+/// A fetched ABI, as kept in the cache.
+#[derive(Serialize, Deserialize)]
+struct VerifiedContract {
+    name: String,
+    abi: Vec<AbiEntry>,
+}
+
+fn cache_path(chain_id: u64, address: &str) -> PathBuf {
+    PathBuf::from(CACHE_DIR).join(format!("{}_{}.json", chain_id, address.to_lowercase()))
+}
+
+/// Name and ABI of the verified contract at `address`, from the cache or from Etherscan.
+/// `name` overrides the contract's name given by Etherscan.
+pub fn fetch_abi(
+    address: &str,
+    chain_id: u64,
+    api_key: Option<&str>,
+    name: Option<&str>,
+) -> anyhow::Result<(String, Vec<AbiEntry>)> {
+    let cache_path = cache_path(chain_id, address);
+    let cached = std::fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<VerifiedContract>(&content).ok());
+    let contract = match cached {
+        Some(contract) => {
+            debug!("Using the cached ABI of {} from {:?}", address, cache_path);
+            contract
+        }
+        None => {
+            let api_key = api_key.context("An Etherscan API key is required, use --etherscan-key or ETHERSCAN_API_KEY")?;
+            let contract = request_abi(address, chain_id, api_key)?;
+            let cached = std::fs::create_dir_all(CACHE_DIR)
+                .and_then(|_| std::fs::write(&cache_path, serde_json::to_string(&contract)?));
+            if let Err(err) = cached {
+                warn!("The ABI couldn't be cached in {:?}: {}", cache_path, err);
+            }
+            contract
+        }
+    };
+    Ok((name.map(str::to_string).unwrap_or(contract.name), contract.abi))
+}
+
+fn request_abi(address: &str, chain_id: u64, api_key: &str) -> anyhow::Result<VerifiedContract> {
+    info!("Fetching the ABI of {} from Etherscan (chain {})", address, chain_id);
+    let chain_id = chain_id.to_string();
+    let response: Response = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?
+        .get(API_URL)
+        .query(&[
+            ("chainid", chain_id.as_str()),
+            ("module", "contract"),
+            ("action", "getsourcecode"),
+            ("address", address),
+            ("apikey", api_key),
+        ])
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .context("Error requesting Etherscan")?;
+
+    if response.status != "1" {
+        bail!("Etherscan error: {}", response.result);
+    }
+    let mut sources: Vec<SourceCode> =
+        serde_json::from_value(response.result).context("Unexpected response from Etherscan")?;
+    let source = sources.pop().with_context(|| format!("No contract at {}", address))?;
+    // Unverified contracts have a message instead of the ABI
+    let abi: Vec<AbiEntry> = serde_json::from_str(&source.abi)
+        .with_context(|| format!("No verified ABI for {}: {}", address, source.abi))?;
+    Ok(VerifiedContract { name: source.contract_name, abi })
+}
@@ -0,0 +1,74 @@
+//! Lookup of the text signatures registered in the 4byte directory for a selector.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use log::{debug, warn};
+use serde::Deserialize;
+
+const API_URL: &str = "https://www.4byte.directory/api/v1/signatures/";
+
+/// Value of the column for selectors without any registered signature.
+pub const UNKNOWN: &str = "UNKNOWN";
+
+#[derive(Deserialize)]
+struct Response {
+    results: Vec<RegisteredSignature>,
+}
+
+#[derive(Deserialize)]
+struct RegisteredSignature {
+    text_signature: String,
+}
+
+/// 4byte directory client, caching the responses during a run so repeated selectors are only queried once.
+pub struct FourByteClient {
+    client: Option<reqwest::blocking::Client>,
+    cache: HashMap<String, String>,
+}
+
+impl FourByteClient {
+    pub fn new() -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|err| warn!("4byte lookups disabled: {}", err))
+            .ok();
+        FourByteClient { client, cache: HashMap::new() }
+    }
+
+    /// Known text signatures of `selector_hex` (semicolon-separated), `UNKNOWN` if there are none,
+    /// or an empty string if the directory couldn't be reached.
+    pub fn lookup(&mut self, selector_hex: &str) -> String {
+        if let Some(known) = self.cache.get(selector_hex) {
+            return known.clone();
+        }
+        let Some(client) = &self.client else {
+            return String::new();
+        };
+
+        debug!("Looking up {} in the 4byte directory", selector_hex);
+        let response = client
+            .get(API_URL)
+            .query(&[("hex_signature", selector_hex)])
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json::<Response>());
+        let known = match response {
+            Ok(response) if response.results.is_empty() => UNKNOWN.to_string(),
+            Ok(response) => {
+                let mut signatures: Vec<String> = response.results.into_iter().map(|r| r.text_signature).collect();
+                signatures.sort();
+                signatures.join(";")
+            }
+            Err(err) => {
+                // Without network every request would fail the same way, so don't try again.
+                warn!("4byte directory unavailable, leaving the column blank: {}", err);
+                self.client = None;
+                String::new()
+            }
+        };
+        self.cache.insert(selector_hex.to_string(), known.clone());
+        known
+    }
+}
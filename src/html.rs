@@ -0,0 +1,105 @@
+//! HTML report (`--format html`): a page with sortable tables per contract, without external resources.
+
+use std::fmt::Write;
+
+use crate::ContractData;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+th { background: #eee; cursor: pointer; user-select: none; }
+code.hash { cursor: copy; }
+code.hash:hover { background: #ffd; }";
+
+// Click on a header to sort its table by that column (again to reverse), on a hash to copy it.
+const SCRIPT: &str = "document.querySelectorAll('th').forEach((th) => th.addEventListener('click', () => {
+  const table = th.closest('table');
+  const body = table.tBodies[0];
+  const column = th.cellIndex;
+  const ascending = th.dataset.order !== 'asc';
+  table.querySelectorAll('th').forEach((other) => delete other.dataset.order);
+  th.dataset.order = ascending ? 'asc' : 'desc';
+  const rows = Array.from(body.rows);
+  rows.sort((a, b) => a.cells[column].textContent.localeCompare(b.cells[column].textContent) * (ascending ? 1 : -1));
+  rows.forEach((row) => body.appendChild(row));
+}));
+document.querySelectorAll('code.hash').forEach((code) => code.addEventListener('click', () => {
+  navigator.clipboard.writeText(code.textContent);
+}));";
+
+/// Escape text for HTML content and attributes.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Cell with a hash (selector or topic) copied on click.
+fn hash_cell(hash: &str) -> String {
+    if hash.is_empty() {
+        String::new()
+    } else {
+        format!("<code class=\"hash\" title=\"Click to copy\">{}</code>", escape(hash))
+    }
+}
+
+/// Table with a title, skipped when there are no rows. Cells are already escaped.
+fn table(html: &mut String, title: &str, columns: &[&str], rows: Vec<Vec<String>>) {
+    if rows.is_empty() {
+        return;
+    }
+    writeln!(html, "<h3>{}</h3>\n<table>\n<thead><tr>", title).unwrap();
+    for column in columns {
+        write!(html, "<th>{}</th>", column).unwrap();
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            write!(html, "<td>{}</td>", cell).unwrap();
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// The whole report: a section per contract with its functions, events and errors.
+pub fn report(contracts: &[ContractData]) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Selectors</title>\n");
+    writeln!(html, "<style>\n{}\n</style>\n</head>\n<body>\n<h1>Selectors</h1>", STYLE).unwrap();
+
+    for contract in contracts.iter().filter(|c| !c.is_empty()) {
+        writeln!(html, "<section>\n<h2>{}</h2>", escape(&contract.name)).unwrap();
+        let functions = contract
+            .functions
+            .iter()
+            .map(|f| {
+                let mutability = f.state_mutability.as_deref().unwrap_or_default();
+                vec![escape(&f.signature), hash_cell(&f.selector), escape(mutability)]
+            })
+            .collect();
+        table(&mut html, "Functions", &["Function", "Selector", "State mutability"], functions);
+        let events = contract
+            .events
+            .iter()
+            .map(|e| {
+                let indexed = e.indexed.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(";");
+                let anonymous = if e.anonymous { "yes" } else { "" };
+                vec![escape(&e.signature), hash_cell(&e.topic), indexed, anonymous.to_string()]
+            })
+            .collect();
+        table(&mut html, "Events", &["Event", "Topic", "Indexed", "Anonymous"], events);
+        let errors = contract
+            .errors
+            .iter()
+            .map(|e| vec![escape(&e.signature), hash_cell(&e.selector)])
+            .collect();
+        table(&mut html, "Errors", &["Error", "Selector"], errors);
+        html.push_str("</section>\n");
+    }
+
+    writeln!(html, "<script>\n{}\n</script>\n</body>\n</html>", SCRIPT).unwrap();
+    html
+}
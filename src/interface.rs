@@ -0,0 +1,188 @@
+//! Reconstruction of a Solidity interface from a contract's ABI (`--emit-interface`).
+
+use std::fmt::Write;
+
+use selector_calculator::{AbiEntry, AbiInput};
+
+use crate::MutabilityGroup;
+
+/// Where a parameter is declared, which decides the data location of reference types.
+#[derive(Clone, Copy)]
+enum Location {
+    // Events and errors' parameters, struct fields.
+    None,
+    Calldata,
+    Memory,
+}
+
+/// Structs declared in the interface, in the order they must be written.
+#[derive(Default)]
+struct Structs {
+    // (name, fields)
+    declared: Vec<(String, Vec<String>)>,
+}
+
+impl Structs {
+    /// Declare the struct of a tuple parameter (and its nested ones) and return its name.
+    /// It's named after the `internalType` when present (`struct Token.Order` -> `Order`),
+    /// otherwise tuples are emitted as anonymous structs `Tuple0`, `Tuple1`, ...
+    fn declare(&mut self, input: &AbiInput) -> String {
+        let fields: Vec<String> = input
+            .components
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, component)| {
+                let name = param_name(component).map(str::to_string).unwrap_or_else(|| format!("field{}", i));
+                format!("{} {}", self.solidity_type(component), name)
+            })
+            .collect();
+
+        let name = input
+            .internal_type
+            .as_deref()
+            .and_then(|t| t.strip_prefix("struct "))
+            .map(|t| t.split('[').next().unwrap_or(t))
+            .map(|t| t.rsplit('.').next().unwrap_or(t).to_string())
+            .unwrap_or_else(|| format!("Tuple{}", self.declared.len()));
+        if !self.declared.iter().any(|(declared, _)| *declared == name) {
+            self.declared.push((name.clone(), fields));
+        }
+        name
+    }
+
+    /// Type as written in Solidity: tuples are replaced by their struct, other types are left as they are.
+    fn solidity_type(&mut self, input: &AbiInput) -> String {
+        match input.param_type.strip_prefix("tuple") {
+            Some(suffix) if input.components.is_some() => format!("{}{}", self.declare(input), suffix),
+            _ => input.param_type.clone(),
+        }
+    }
+
+    /// Parameters' list, e.g. `address to, uint256 value`.
+    fn params(&mut self, inputs: &Option<Vec<AbiInput>>, location: Location, indexed: bool) -> String {
+        inputs
+            .iter()
+            .flatten()
+            .map(|input| {
+                let mut param = self.solidity_type(input);
+                if is_reference_type(input) {
+                    match location {
+                        Location::None => {}
+                        Location::Calldata => param.push_str(" calldata"),
+                        Location::Memory => param.push_str(" memory"),
+                    }
+                }
+                if indexed && input.indexed {
+                    param.push_str(" indexed");
+                }
+                if let Some(name) = param_name(input) {
+                    param.push(' ');
+                    param.push_str(name);
+                }
+                param
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn param_name(input: &AbiInput) -> Option<&str> {
+    input.name.as_deref().filter(|name| !name.is_empty())
+}
+
+/// Arrays, tuples, `bytes` and `string` need a data location in functions.
+fn is_reference_type(input: &AbiInput) -> bool {
+    let param_type = input.param_type.as_str();
+    param_type.ends_with(']') || param_type == "tuple" || param_type == "bytes" || param_type == "string"
+}
+
+/// Repeated ABI entries (e.g. inherited and overridden functions) are only declared once.
+fn push_unique<T: PartialEq>(lines: &mut Vec<T>, line: T) {
+    if !lines.contains(&line) {
+        lines.push(line);
+    }
+}
+
+/// Source of `interface I<Contract>` with the events, errors and functions of the ABI.
+/// With `group_by_mutability`, the functions are sorted in commented groups: view and pure, nonpayable, payable.
+pub fn solidity_interface(contract_name: &str, abi_entries: &[AbiEntry], group_by_mutability: bool) -> String {
+    let mut structs = Structs::default();
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+    let mut functions = Vec::new();
+
+    for entry in abi_entries {
+        let name = entry.name.as_deref().unwrap_or("unknown");
+        let group = MutabilityGroup::of(entry.state_mutability.as_deref());
+        match entry.kind.as_str() {
+            "event" => {
+                let anonymous = if entry.anonymous { " anonymous" } else { "" };
+                let params = structs.params(&entry.inputs, Location::None, true);
+                push_unique(&mut events, format!("event {}({}){};", name, params, anonymous));
+            }
+            "error" => {
+                let params = structs.params(&entry.inputs, Location::None, false);
+                push_unique(&mut errors, format!("error {}({});", name, params));
+            }
+            "function" => {
+                let params = structs.params(&entry.inputs, Location::Calldata, false);
+                let mut function = format!("function {}({}) external", name, params);
+                // 'nonpayable' is the default, it isn't written
+                if let Some(mutability @ ("view" | "pure" | "payable")) = entry.state_mutability.as_deref() {
+                    write!(function, " {}", mutability).unwrap();
+                }
+                if entry.outputs.as_ref().is_some_and(|outputs| !outputs.is_empty()) {
+                    let returns = structs.params(&entry.outputs, Location::Memory, false);
+                    write!(function, " returns ({})", returns).unwrap();
+                }
+                function.push(';');
+                push_unique(&mut functions, (group, function));
+            }
+            "receive" => functions.push((MutabilityGroup::Payable, "receive() external payable;".to_string())),
+            "fallback" => {
+                let payable = if entry.state_mutability.as_deref() == Some("payable") { " payable" } else { "" };
+                functions.push((group, format!("fallback() external{};", payable)));
+            }
+            _ => {} // Interfaces can't declare constructors
+        }
+    }
+
+    if group_by_mutability {
+        functions.sort();
+    }
+    let mut function_lines = Vec::new();
+    let mut previous = None;
+    for (group, function) in functions {
+        if group_by_mutability && previous != Some(group) {
+            function_lines.push(format!("// {}", group.label()));
+            previous = Some(group);
+        }
+        function_lines.push(function);
+    }
+
+    let mut source = String::from("// SPDX-License-Identifier: UNLICENSED\npragma solidity ^0.8.0;\n\n");
+    writeln!(source, "/// Reconstructed from the ABI of {}.", contract_name).unwrap();
+    writeln!(source, "interface I{} {{", contract_name).unwrap();
+    let sections = [
+        structs
+            .declared
+            .iter()
+            .map(|(name, fields)| {
+                let fields: String = fields.iter().map(|field| format!("        {};\n", field)).collect();
+                format!("struct {} {{\n{}    }}", name, fields)
+            })
+            .collect::<Vec<_>>(),
+        events,
+        errors,
+        function_lines,
+    ];
+    let blocks: Vec<String> = sections
+        .iter()
+        .filter(|lines| !lines.is_empty())
+        .map(|lines| lines.iter().map(|line| format!("    {}\n", line)).collect())
+        .collect();
+    source.push_str(&blocks.join("\n"));
+    source.push_str("}\n");
+    source
+}
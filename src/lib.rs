@@ -0,0 +1,758 @@
+//! Computation of function selectors, event topics and error selectors from a contract's ABI.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+/// An entry of a contract's ABI (function, event, error, ...).
+#[derive(Clone, Deserialize, Serialize)]
+pub struct AbiEntry {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: Option<String>,
+    pub inputs: Option<Vec<AbiInput>>,
+    // Return values, only for functions.
+    pub outputs: Option<Vec<AbiInput>>,
+    // For events, if not specified we assum false.
+    #[serde(default)]
+    pub anonymous: bool,
+    // view, pure, payable or nonpayable. Missing on old ABIs.
+    #[serde(rename = "stateMutability")]
+    pub state_mutability: Option<String>,
+}
+
+/// A parameter of an ABI entry.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct AbiInput {
+    // May be empty, e.g. unnamed return values.
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub param_type: String,
+    // Solidity type, e.g. `struct Token.Order`. Missing on old ABIs.
+    #[serde(rename = "internalType")]
+    pub internal_type: Option<String>,
+    #[serde(default)]
+    pub components: Option<Vec<AbiInput>>,
+    // Only meaningful for event inputs.
+    #[serde(default)]
+    pub indexed: bool,
+}
+
+/// Error of the library's functions, for callers that can't just print a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelectorError {
+    // A signature written by hand that can't be parsed, with why.
+    MalformedSignature(String),
+    // A type that isn't an ABI type, e.g. `uint257` or a struct's name.
+    InvalidType(String),
+    // A number of bytes for the selectors outside 1 to 32.
+    InvalidSelectorLength(usize),
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorError::MalformedSignature(message) => write!(f, "{}", message),
+            SelectorError::InvalidType(param_type) => write!(f, "Invalid ABI type '{}'", param_type),
+            SelectorError::InvalidSelectorLength(bytes) => {
+                write!(f, "Invalid selector length {}, expected 1 to 32 bytes", bytes)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+/// Split a type into its base and its array suffix, e.g. `uint256[2][]` -> (`uint256`, `[2][]`).
+fn split_array_suffix(param_type: &str) -> (&str, &str) {
+    match param_type.find('[') {
+        Some(i) => param_type.split_at(i),
+        None => (param_type, ""),
+    }
+}
+
+/// Rewrite the `uint`/`int` aliases into `uint256`/`int256`.
+fn normalize_alias(base: &str) -> &str {
+    match base {
+        "uint" => "uint256",
+        "int" => "int256",
+        other => other,
+    }
+}
+
+/// ABI type of a Solidity type left unresolved, as found in hand-written ABIs: `address payable` and contracts
+/// (`contract IERC20`, or a contract's name with a `contract ...` internal type) are addresses, enums are `uint8`.
+/// `None` for the ABI's own types and the unknown ones.
+fn resolved_base(base: &str, internal_type: Option<&str>) -> Option<&'static str> {
+    if is_valid_base(base) {
+        return None;
+    }
+    let internal_type = internal_type.unwrap_or_default();
+    if base == "address payable" || base.starts_with("contract ") || internal_type.starts_with("contract ") {
+        Some("address")
+    } else if base.starts_with("enum ") || internal_type.starts_with("enum ") {
+        // Enums have at most 256 members
+        Some("uint8")
+    } else {
+        None
+    }
+}
+
+/// Unresolved types of an ABI entry's inputs (components included) with the ABI type they're read as,
+/// e.g. (`address payable`, `address`).
+pub fn resolved_types(entry: &AbiEntry) -> Vec<(String, String)> {
+    fn check(inputs: &[AbiInput], resolved: &mut Vec<(String, String)>) {
+        for input in inputs {
+            let (base, suffix) = split_array_suffix(&input.param_type);
+            if let Some(abi_type) = resolved_base(base, input.internal_type.as_deref()) {
+                resolved.push((input.param_type.clone(), format!("{}{}", abi_type, suffix)));
+            }
+            if let Some(components) = &input.components {
+                check(components, resolved);
+            }
+        }
+    }
+    let mut resolved = Vec::new();
+    check(entry.inputs.as_deref().unwrap_or_default(), &mut resolved);
+    resolved
+}
+
+/// Size of a `uint<M>`/`int<M>`/`fixed<M>x<N>`: 8 to 256 bits, in steps of 8.
+fn is_valid_bits(bits: &str) -> bool {
+    bits.parse::<u32>().is_ok_and(|bits| (8..=256).contains(&bits) && bits % 8 == 0) && !bits.starts_with('0')
+}
+
+/// Base type (without array suffix) defined by the ABI specification, `tuple` included.
+fn is_valid_base(base: &str) -> bool {
+    if matches!(base, "address" | "bool" | "string" | "bytes" | "function" | "tuple" | "uint" | "int" | "fixed" | "ufixed") {
+        return true;
+    }
+    if let Some(size) = base.strip_prefix("bytes") {
+        return size.parse::<u32>().is_ok_and(|size| (1..=32).contains(&size)) && !size.starts_with('0');
+    }
+    if let Some(bits) = base.strip_prefix("uint").or_else(|| base.strip_prefix("int")) {
+        return is_valid_bits(bits);
+    }
+    if let Some(size) = base.strip_prefix("ufixed").or_else(|| base.strip_prefix("fixed")) {
+        return size.split_once('x').is_some_and(|(bits, decimals)| {
+            is_valid_bits(bits) && decimals.parse::<u32>().is_ok_and(|decimals| decimals <= 80)
+        });
+    }
+    false
+}
+
+/// Array suffix made of `[]` or `[<length>]` dimensions, a fixed length being at least 1 (`[0]` is invalid).
+fn is_valid_suffix(suffix: &str) -> bool {
+    let mut rest = suffix;
+    while let Some(dimension) = rest.strip_prefix('[') {
+        let Some((length, after)) = dimension.split_once(']') else {
+            return false;
+        };
+        if !length.chars().all(|c| c.is_ascii_digit()) || length.starts_with('0') {
+            return false;
+        }
+        rest = after;
+    }
+    rest.is_empty()
+}
+
+/// Types of an ABI entry's inputs (components included) that aren't valid ABI types, e.g. `uint257` or `bytes33`.
+/// Their selector is still computed, but it can't match any real contract.
+pub fn invalid_types(entry: &AbiEntry) -> Vec<String> {
+    fn check(inputs: &[AbiInput], invalid: &mut Vec<String>) {
+        for input in inputs {
+            let (base, suffix) = split_array_suffix(&input.param_type);
+            let resolved = resolved_base(base, input.internal_type.as_deref()).is_some();
+            if !(is_valid_base(base) || resolved) || !is_valid_suffix(suffix) {
+                invalid.push(input.param_type.clone());
+            }
+            if let Some(components) = &input.components {
+                check(components, invalid);
+            }
+        }
+    }
+    let mut invalid = Vec::new();
+    check(entry.inputs.as_deref().unwrap_or_default(), &mut invalid);
+    invalid
+}
+
+/// Canonical ABI type of an input: `tuple` is expanded into `(t1,t2,...)`, recursing through
+/// nested components, and aliases (`uint`, `int`) are rewritten into their canonical form, like the Solidity types
+/// left unresolved (`address payable`, contracts and enums, see `resolved_types`).
+/// Array suffixes are kept as they are, e.g. `tuple[3]` -> `(t1,t2,...)[3]` or `uint[2][]` -> `uint256[2][]`.
+pub fn canonical_type(input: &AbiInput) -> String {
+    let (base, suffix) = split_array_suffix(&input.param_type);
+    match (base, &input.components) {
+        ("tuple", Some(components)) => {
+            let component_types: Vec<String> = components.iter().map(canonical_type).collect();
+            format!("({}){}", component_types.join(","), suffix)
+        }
+        _ => {
+            let base = resolved_base(base, input.internal_type.as_deref()).unwrap_or(normalize_alias(base));
+            format!("{}{}", base, suffix)
+        }
+    }
+}
+
+/// Canonical signature of an ABI entry, e.g. `transfer(address,uint256)`.
+/// Constructors have no name, their pseudo-signature is `constructor(types)`.
+pub fn signature_from_abi(entry: &AbiEntry) -> String {
+    let unnamed = if entry.kind == "constructor" { "constructor" } else { "unknown" };
+    let name = entry.name.as_deref().unwrap_or(unnamed);
+    let input_types = entry
+        .inputs
+        .iter()
+        .flatten()
+        .map(canonical_type)
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{}({})", name, input_types)
+}
+
+/// Canonical types of an event's parameters, split into the indexed ones (in topics 1, 2, ... of its logs)
+/// and the others (ABI-encoded in the data), each in the signature's order.
+pub fn event_layout(entry: &AbiEntry) -> (Vec<String>, Vec<String>) {
+    let (indexed, data): (Vec<&AbiInput>, Vec<&AbiInput>) =
+        entry.inputs.iter().flatten().partition(|input| input.indexed);
+    (indexed.into_iter().map(canonical_type).collect(), data.into_iter().map(canonical_type).collect())
+}
+
+/// Split a parameters' list at its top-level commas, checking the parentheses are balanced.
+fn split_params(params: &str) -> Result<Vec<&str>, SelectorError> {
+    let unbalanced = || SelectorError::MalformedSignature(format!("Unbalanced parentheses in '({})'", params));
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1).ok_or_else(unbalanced)?,
+            ',' if depth == 0 => {
+                parts.push(&params[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(unbalanced());
+    }
+    parts.push(&params[start..]);
+    Ok(parts)
+}
+
+/// Canonical types of a parameters' list written by hand, e.g. `address to, uint amount` -> `address,uint256`.
+/// Each type must be an ABI type, a struct's name has no canonical form.
+fn canonical_params(params: &str) -> Result<String, SelectorError> {
+    if params.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let mut types = Vec::new();
+    for param in split_params(params)? {
+        let param = param.trim();
+        let canonical = if let Some(inner) = param.strip_prefix('(') {
+            // Tuple: its components, then an optional array suffix and name
+            let close = inner
+                .rfind(')')
+                .ok_or_else(|| SelectorError::MalformedSignature(format!("Unbalanced parentheses in '{}'", param)))?;
+            let rest = &inner[close + 1..];
+            let suffix = if rest.starts_with('[') { rest.split_whitespace().next().unwrap_or_default() } else { "" };
+            if !is_valid_suffix(suffix) {
+                return Err(SelectorError::InvalidType(format!("({}){}", &inner[..close], suffix)));
+            }
+            format!("({}){}", canonical_params(&inner[..close])?, suffix)
+        } else {
+            // The type is the 1st word, the name and data location are dropped
+            let param_type = param
+                .split_whitespace()
+                .next()
+                .ok_or_else(|| SelectorError::MalformedSignature(format!("Empty parameter in '({})'", params)))?;
+            let (base, suffix) = split_array_suffix(param_type);
+            // A hand-written `tuple` has no components
+            if base == "tuple" || !is_valid_base(base) || !is_valid_suffix(suffix) {
+                return Err(SelectorError::InvalidType(param_type.to_string()));
+            }
+            format!("{}{}", normalize_alias(base), suffix)
+        };
+        types.push(canonical);
+    }
+    Ok(types.join(","))
+}
+
+/// Canonical form of a signature written by hand, e.g. `transfer(address to, uint amount)` -> `transfer(address,uint256)`:
+/// spaces, parameters' names and data locations are dropped and aliases are rewritten, like for the ABI's entries.
+pub fn canonical_signature(signature: &str) -> Result<String, SelectorError> {
+    let signature = signature.trim();
+    let malformed = |message: String| SelectorError::MalformedSignature(message);
+    let (name, rest) =
+        signature.split_once('(').ok_or_else(|| malformed(format!("Missing parameters' list in '{}'", signature)))?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+        return Err(malformed(format!("Invalid name '{}' in '{}'", name, signature)));
+    }
+    let params = rest.strip_suffix(')').ok_or_else(|| malformed(format!("Unbalanced parentheses in '{}'", signature)))?;
+    let params = canonical_params(params).map_err(|err| match err {
+        SelectorError::MalformedSignature(message) => malformed(format!("{} ('{}')", message, signature)),
+        other => other,
+    })?;
+    Ok(format!("{}({})", name, params))
+}
+
+/// Names of an ABI entry's parameters, comma-separated like its signature's types, e.g. `from,to,value`.
+/// Unnamed parameters are left empty.
+pub fn parameter_names(entry: &AbiEntry) -> String {
+    entry
+        .inputs
+        .iter()
+        .flatten()
+        .map(|input| input.name.as_deref().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A warning or an error of a `forge compile` output, e.g. `Warning (2072): Unused local variable.`
+/// or `TypeError (9574): Type uint256 is not implicitly convertible...`.
+pub struct CompileDiagnostic {
+    pub is_error: bool,
+    pub message: String,
+    // The `--> src/Token.sol:12:5` line below the message, without the arrow.
+    pub location: Option<String>,
+}
+
+/// Warnings and errors of a `forge compile` output, in their order. The solc ones start a line with their kind
+/// (`Warning`, `Error`, `ParserError`, `TypeError`, ...) followed by their code or a colon.
+pub fn compile_diagnostics(compile_output: &str) -> Vec<CompileDiagnostic> {
+    let mut diagnostics: Vec<CompileDiagnostic> = Vec::new();
+    for line in compile_output.lines() {
+        let kind = line.split([' ', ':']).next().unwrap_or_default();
+        let is_error = kind.ends_with("Error") && kind.starts_with(|c: char| c.is_ascii_uppercase());
+        let rest = line[kind.len()..].trim_start();
+        if (is_error || kind == "Warning") && (rest.starts_with('(') || rest.starts_with(':')) {
+            // Foundry's own 'Error: Compiler run failed:' line, the errors are below
+            let message = rest.trim_start_matches(':').trim();
+            if message.is_empty() || message.starts_with("Compiler run failed") {
+                continue;
+            }
+            diagnostics.push(CompileDiagnostic { is_error, message: line.trim_end().to_string(), location: None });
+        } else if let (Some(location), Some(last)) = (line.trim_start().strip_prefix("--> "), diagnostics.last_mut()) {
+            last.location.get_or_insert_with(|| location.trim_end().trim_end_matches(':').to_string());
+        }
+    }
+    diagnostics
+}
+
+/// Dotted names and canonical types of a tuple input's fields, recursing through nested tuples, e.g.
+/// `order.maker` and `order.fee.amount`. Arrays of tuples have their suffix in the path, e.g. `orders[].maker`.
+/// Empty for the other types, unnamed fields are left empty in the path (`order.`).
+pub fn component_names(input: &AbiInput) -> Vec<(String, String)> {
+    let (base, suffix) = split_array_suffix(&input.param_type);
+    let (Some(components), "tuple") = (&input.components, base) else {
+        return Vec::new();
+    };
+    let path = format!("{}{}", input.name.as_deref().unwrap_or_default(), suffix);
+    let mut fields = Vec::new();
+    for component in components {
+        let nested = component_names(component);
+        if nested.is_empty() {
+            let name = component.name.as_deref().unwrap_or_default();
+            fields.push((format!("{}.{}", path, name), canonical_type(component)));
+        } else {
+            fields.extend(nested.into_iter().map(|(name, field_type)| (format!("{}.{}", path, name), field_type)));
+        }
+    }
+    fields
+}
+
+/// Name of the struct of a tuple parameter, from its `internalType`, e.g. `Order` for `struct Vault.Order[]`.
+fn struct_name(input: &AbiInput) -> Option<&str> {
+    let internal_type = input.internal_type.as_deref()?.strip_prefix("struct ")?;
+    split_array_suffix(internal_type).0.rsplit('.').next()
+}
+
+/// Members (`type name`) of the structs of tuple parameters, nested ones included, by name. `None` for a struct
+/// with a member tuple that has no struct name.
+fn collect_structs<'a>(inputs: &'a [AbiInput], structs: &mut BTreeMap<&'a str, Option<Vec<String>>>) {
+    for input in inputs {
+        let Some(components) = &input.components else {
+            continue;
+        };
+        collect_structs(components, structs);
+        let Some(name) = struct_name(input) else {
+            continue;
+        };
+        let members = components
+            .iter()
+            .map(|member| {
+                let member_type = match member.components {
+                    Some(_) => format!("{}{}", struct_name(member)?, split_array_suffix(&member.param_type).1),
+                    None => canonical_type(member),
+                };
+                Some(format!("{} {}", member_type, member.name.as_deref().unwrap_or_default()))
+            })
+            .collect();
+        structs.entry(name).or_insert(members);
+    }
+}
+
+/// EIP-712 encoded type of each struct of an ABI's parameters, sorted by name, e.g.
+/// `Mail(Person from,Person to,string contents)Person(address wallet,string name)`: its members, then the
+/// structs it references, sorted by name. Its type hash is the keccak256 hash of the encoded type.
+/// Structs are named by their tuple's `internalType`, those without one can't be encoded and are left out.
+pub fn eip712_types(entries: &[AbiEntry]) -> Vec<(String, String)> {
+    let mut structs = BTreeMap::new();
+    for entry in entries {
+        collect_structs(entry.inputs.as_deref().unwrap_or_default(), &mut structs);
+        collect_structs(entry.outputs.as_deref().unwrap_or_default(), &mut structs);
+    }
+    let mut types = Vec::new();
+    'structs: for (name, members) in &structs {
+        let Some(members) = members else {
+            continue;
+        };
+        // Structs referenced by the members, directly or not
+        let mut referenced = BTreeSet::new();
+        let mut pending = vec![members];
+        while let Some(members) = pending.pop() {
+            for member in members {
+                let member_type = member.split(' ').next().unwrap_or_default();
+                let base = split_array_suffix(member_type).0;
+                match structs.get(base) {
+                    Some(Some(nested)) if referenced.insert(base) => pending.push(nested),
+                    Some(None) => continue 'structs,
+                    _ => {}
+                }
+            }
+        }
+        let mut encoded = format!("{}({})", name, members.join(","));
+        for referenced in referenced.into_iter().filter(|referenced| referenced != name) {
+            if let Some(Some(members)) = structs.get(referenced) {
+                encoded.push_str(&format!("{}({})", referenced, members.join(",")));
+            }
+        }
+        types.push((name.to_string(), encoded));
+    }
+    types
+}
+
+/// Full keccak256 hash of a signature.
+pub fn signature_hash(signature: &str) -> [u8; 32] {
+    HashAlgorithm::Keccak256.digest(signature)
+}
+
+/// Selector of a function or error: the 4 1st bytes of the signature's keccak256 hash.
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = signature_hash(signature);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// ERC-165 interface ID of a set of functions: the XOR of their selectors.
+pub fn interface_id(selectors: impl IntoIterator<Item = [u8; 4]>) -> [u8; 4] {
+    selectors.into_iter().fold([0; 4], |id, selector| std::array::from_fn(|i| id[i] ^ selector[i]))
+}
+
+/// Topic of an event: the full keccak256 hash of its signature.
+pub fn event_topic(signature: &str) -> [u8; 32] {
+    signature_hash(signature)
+}
+
+/// Selector of an ABI entry if it has one, i.e. for functions and errors.
+/// Events, constructors, `receive` and `fallback` give `None`.
+pub fn selector_for(entry: &AbiEntry) -> Option<[u8; 4]> {
+    matches!(entry.kind.as_str(), "function" | "error").then(|| function_selector(&signature_from_abi(entry)))
+}
+
+/// Topic of an ABI entry if it's an event, `None` for anonymous events as their logs have no topic0.
+pub fn topic_for(entry: &AbiEntry) -> Option<[u8; 32]> {
+    (entry.kind == "event" && !entry.anonymous).then(|| event_topic(&signature_from_abi(entry)))
+}
+
+/// Selectors of the functions dispatched by a contract's runtime bytecode, sorted and without repetitions.
+/// They're found in the dispatcher's comparisons `PUSH4 <selector> EQ PUSH2 <destination> JUMPI`, also with a `DUP`
+/// before `EQ` or a 1 or 3 bytes destination. It's a heuristic: other dispatchers (e.g. hand-written or binary
+/// search ones) aren't recognized.
+pub fn dispatch_selectors(bytecode: &[u8]) -> Vec<[u8; 4]> {
+    const PUSH1: u8 = 0x60;
+    const PUSH3: u8 = 0x62;
+    const PUSH4: u8 = 0x63;
+    const PUSH32: u8 = 0x7f;
+    const EQ: u8 = 0x14;
+    const JUMPI: u8 = 0x57;
+    const DUP1: u8 = 0x80;
+    const DUP16: u8 = 0x8f;
+
+    // Each opcode with its immediate data, so that data bytes aren't read as opcodes
+    let mut instructions: Vec<(u8, &[u8])> = Vec::new();
+    let mut i = 0;
+    while i < bytecode.len() {
+        let opcode = bytecode[i];
+        let size = if (PUSH1..=PUSH32).contains(&opcode) { (opcode - PUSH1 + 1) as usize } else { 0 };
+        let end = (i + 1 + size).min(bytecode.len());
+        instructions.push((opcode, &bytecode[i + 1..end]));
+        i = end;
+    }
+
+    let mut selectors = BTreeSet::new();
+    for (k, (opcode, data)) in instructions.iter().enumerate() {
+        // Truncated at the end of the bytecode otherwise
+        let (PUSH4, Ok(selector)) = (*opcode, <[u8; 4]>::try_from(*data)) else {
+            continue;
+        };
+        let mut next = instructions[k + 1..].iter().map(|(opcode, _)| *opcode).peekable();
+        next.next_if(|opcode| (DUP1..=DUP16).contains(opcode));
+        let comparison: Vec<u8> = next.take(3).collect();
+        if let [EQ, push, JUMPI] = comparison[..] {
+            if (PUSH1..=PUSH3).contains(&push) {
+                selectors.insert(selector);
+            }
+        }
+    }
+    selectors.into_iter().collect()
+}
+
+thread_local! {
+    // Hashes already computed by each thread, by algorithm and signature: the same signatures (`transfer(...)`,
+    // `Transfer(...)`, ...) come up in many contracts. A cache per thread needs no lock between rayon's threads.
+    static HASHES: RefCell<HashMap<HashAlgorithm, HashMap<String, [u8; 32]>>> = RefCell::new(HashMap::new());
+}
+
+/// Forget the hashes computed by the current thread, so the cache doesn't keep growing with the signatures of
+/// each run of `--watch`.
+pub fn clear_hash_cache() {
+    HASHES.with(|hashes| hashes.borrow_mut().clear());
+}
+
+/// Hash function of the signatures. The EVM uses keccak256, some other chains and VMs SHA-256.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    #[default]
+    Keccak256,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Parse the name given to '--hash': `keccak256` or `sha256`.
+    pub fn from_name(name: &str) -> Option<HashAlgorithm> {
+        match name {
+            "keccak256" => Some(HashAlgorithm::Keccak256),
+            "sha256" => Some(HashAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+
+    /// Full hash of a signature, computed once per thread for each signature.
+    pub fn digest(self, signature: &str) -> [u8; 32] {
+        HASHES.with(|hashes| {
+            let mut hashes = hashes.borrow_mut();
+            let hashes = hashes.entry(self).or_default();
+            if let Some(hash) = hashes.get(signature) {
+                return *hash;
+            }
+            let hash = self.compute(signature);
+            hashes.insert(signature.to_string(), hash);
+            hash
+        })
+    }
+
+    /// Hash of a signature, without the cache.
+    fn compute(self, signature: &str) -> [u8; 32] {
+        match self {
+            HashAlgorithm::Keccak256 => Keccak256::digest(signature.as_bytes()).into(),
+            HashAlgorithm::Sha256 => Sha256::digest(signature.as_bytes()).into(),
+        }
+    }
+}
+
+/// How selectors and topics are computed: the hash function, and how many of its 1st bytes make a selector.
+/// The default one is the EVM's, keccak256 with 4 bytes selectors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelectorHasher {
+    pub algorithm: HashAlgorithm,
+    // Between 1 and 32.
+    pub selector_bytes: usize,
+}
+
+impl Default for SelectorHasher {
+    fn default() -> Self {
+        SelectorHasher { algorithm: HashAlgorithm::Keccak256, selector_bytes: 4 }
+    }
+}
+
+impl SelectorHasher {
+    /// Hasher keeping `selector_bytes` bytes of `algorithm`'s hashes, between 1 and 32.
+    pub fn new(algorithm: HashAlgorithm, selector_bytes: usize) -> Result<SelectorHasher, SelectorError> {
+        if !(1..=32).contains(&selector_bytes) {
+            return Err(SelectorError::InvalidSelectorLength(selector_bytes));
+        }
+        Ok(SelectorHasher { algorithm, selector_bytes })
+    }
+
+    /// Whether it computes the EVM's selectors, the ones tools like `cast` or forge give.
+    pub fn is_evm(&self) -> bool {
+        *self == SelectorHasher::default()
+    }
+
+    /// Full hash of a signature.
+    pub fn hash(&self, signature: &str) -> [u8; 32] {
+        self.algorithm.digest(signature)
+    }
+
+    /// Selector of a function or error: the `selector_bytes` 1st bytes of the signature's hash.
+    pub fn selector(&self, signature: &str) -> Vec<u8> {
+        self.hash(signature)[..self.selector_bytes.clamp(1, 32)].to_vec()
+    }
+
+    /// Topic of an event: the full hash of its signature.
+    pub fn topic(&self, signature: &str) -> [u8; 32] {
+        self.hash(signature)
+    }
+}
+
+/// Remove the `//` and `/* */` comments of a Solidity source.
+fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(i) = rest.find('/') {
+        stripped.push_str(&rest[..i]);
+        let after = &rest[i..];
+        if after.starts_with("//") {
+            rest = after.find('\n').map(|end| &after[end..]).unwrap_or("");
+        } else if let Some(comment) = after.strip_prefix("/*") {
+            rest = comment.find("*/").map(|end| &comment[end + 2..]).unwrap_or("");
+            stripped.push(' ');
+        } else {
+            stripped.push('/');
+            rest = &after[1..];
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// Names of the contracts, libraries and interfaces declared in a Solidity source, in order.
+/// They don't have to match the file's name, e.g. `contract MyToken` in `My Token.sol`.
+pub fn declared_contracts(source: &str) -> Vec<String> {
+    let source = strip_comments(source);
+    let mut words = source
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+        .filter(|word| !word.is_empty());
+    let mut names = Vec::new();
+    while let Some(word) = words.next() {
+        if matches!(word, "contract" | "library" | "interface") {
+            if let Some(name) = words.next() {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// A contract's name usable as a file name on any filesystem: characters other than ASCII letters,
+/// digits, `_`, `-` and `.` are replaced by `_`, e.g. `My Token` -> `My_Token`.
+pub fn safe_file_name(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+        .collect();
+    // Empty names, or made only of dots, would point to a folder
+    if safe.chars().all(|c| c == '.') {
+        "_".repeat(safe.len().max(1))
+    } else {
+        safe
+    }
+}
+
+/// A notable function selector, flagged by '--flag-known'.
+pub struct KnownSelector {
+    pub selector: [u8; 4],
+    // Empty for the zero selector, which is no function's.
+    pub signature: &'static str,
+    // Standard or role defining it, e.g. `ERC-165`.
+    pub label: &'static str,
+}
+
+/// Selectors worth a look in a review: the standard interfaces' ones (a contract with one of them is expected
+/// to conform), the callbacks of tokens' safe transfers, and the admin functions of proxies and ownable contracts
+/// (clashing with them by accident e.g. makes a function unreachable behind a proxy).
+pub const KNOWN_SELECTORS: &[KnownSelector] = &[
+    KnownSelector { selector: [0x00, 0x00, 0x00, 0x00], signature: "", label: "zero selector" },
+    KnownSelector { selector: [0x01, 0xff, 0xc9, 0xa7], signature: "supportsInterface(bytes4)", label: "ERC-165" },
+    KnownSelector {
+        selector: [0x15, 0x0b, 0x7a, 0x02],
+        signature: "onERC721Received(address,address,uint256,bytes)",
+        label: "ERC-721 receiver",
+    },
+    KnownSelector {
+        selector: [0xf2, 0x3a, 0x6e, 0x61],
+        signature: "onERC1155Received(address,address,uint256,uint256,bytes)",
+        label: "ERC-1155 receiver",
+    },
+    KnownSelector {
+        selector: [0xbc, 0x19, 0x7c, 0x81],
+        signature: "onERC1155BatchReceived(address,address,uint256[],uint256[],bytes)",
+        label: "ERC-1155 receiver",
+    },
+    KnownSelector {
+        selector: [0x16, 0x26, 0xba, 0x7e],
+        signature: "isValidSignature(bytes32,bytes)",
+        label: "ERC-1271",
+    },
+    KnownSelector { selector: [0x52, 0xd1, 0x90, 0x2d], signature: "proxiableUUID()", label: "ERC-1822 proxy" },
+    KnownSelector { selector: [0x36, 0x59, 0xcf, 0xe6], signature: "upgradeTo(address)", label: "proxy admin" },
+    KnownSelector {
+        selector: [0x4f, 0x1e, 0xf2, 0x86],
+        signature: "upgradeToAndCall(address,bytes)",
+        label: "proxy admin",
+    },
+    KnownSelector { selector: [0x8f, 0x28, 0x39, 0x70], signature: "changeAdmin(address)", label: "proxy admin" },
+    KnownSelector { selector: [0xf8, 0x51, 0xa4, 0x40], signature: "admin()", label: "proxy admin" },
+    KnownSelector { selector: [0x5c, 0x60, 0xda, 0x1b], signature: "implementation()", label: "proxy admin" },
+    KnownSelector { selector: [0x8d, 0xa5, 0xcb, 0x5b], signature: "owner()", label: "ownable" },
+    KnownSelector { selector: [0xf2, 0xfd, 0xe3, 0x8b], signature: "transferOwnership(address)", label: "ownable" },
+    KnownSelector { selector: [0x71, 0x50, 0x18, 0xa6], signature: "renounceOwnership()", label: "ownable" },
+];
+
+/// The notable selector `selector` is, if it's one of `KNOWN_SELECTORS`.
+pub fn known_selector(selector: [u8; 4]) -> Option<&'static KnownSelector> {
+    KNOWN_SELECTORS.iter().find(|known| known.selector == selector)
+}
+
+/// Solc versions listed by a `forge compile` output, from its `Compiling 3 files with Solc 0.8.24` lines
+/// (`with 0.8.19` for older forge versions), sorted and each one once. Vyper's versions are left out.
+pub fn compiler_versions(compile_output: &str) -> Vec<String> {
+    let versions: BTreeSet<&str> = compile_output
+        .lines()
+        .filter_map(|line| line.split_once("Compiling ")?.1.split_once(" with ").map(|(_, compiler)| compiler))
+        .filter_map(|compiler| {
+            let version = compiler.strip_prefix("Solc ").unwrap_or(compiler).split_whitespace().next()?;
+            version.starts_with(|c: char| c.is_ascii_digit()).then_some(version)
+        })
+        .collect();
+    versions.into_iter().map(str::to_string).collect()
+}
+
+/// Distinct signatures sharing the same selector computed by `hasher`, as `(selector, signatureA, signatureB)`.
+/// Each colliding signature is paired with the first one seen for that selector.
+pub fn find_collisions<'a, I>(signatures: I, hasher: &SelectorHasher) -> Vec<(Vec<u8>, String, String)>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut by_selector: BTreeMap<Vec<u8>, Vec<&str>> = BTreeMap::new();
+    for signature in signatures {
+        let seen = by_selector.entry(hasher.selector(signature)).or_default();
+        if !seen.contains(&signature) {
+            seen.push(signature);
+        }
+    }
+
+    let mut collisions = Vec::new();
+    for (selector, signatures) in by_selector {
+        for other in &signatures[1..] {
+            collisions.push((selector.clone(), signatures[0].to_string(), other.to_string()));
+        }
+    }
+    collisions
+}
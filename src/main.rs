@@ -1,264 +1,2828 @@
+mod cache;
+mod config;
+mod diff;
+mod etherscan;
+mod fourbyte;
+mod html;
+mod interface;
+mod output;
+mod sqlite;
+mod watch;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env;
-use std::fs::{create_dir_all, File};
-use std::io::{Write, BufWriter};
-use std::path::Path;
+use std::ffi::OsStr;
+use std::fs::create_dir_all;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
-use serde::Deserialize;
+use std::time::Duration;
+use anyhow::{anyhow, bail, Context};
+use log::{debug, error, info, warn, LevelFilter};
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
-use sha3::{Digest, Keccak256};
+use rayon::prelude::*;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use indicatif_log_bridge::LogWrapper;
+use selector_calculator::{
+    canonical_signature, canonical_type, clear_hash_cache, compile_diagnostics, compiler_versions, component_names,
+    declared_contracts, dispatch_selectors, eip712_types, event_layout, event_topic, find_collisions, function_selector,
+    invalid_types, known_selector, parameter_names, resolved_types, signature_from_abi, AbiEntry, HashAlgorithm,
+    SelectorHasher,
+};
+use cache::{Cache, FileAbis};
+use fourbyte::FourByteClient;
+use output::OutputPaths;
+
+/// Folder where 'forge compile' writes the compiled artifacts.
+const ARTIFACTS_DIR: &str = "out";
 
+/// Times 'forge inspect' (or 'vyper') is spawned before giving up.
+const SPAWN_ATTEMPTS: usize = 3;
+
+/// Output of 'solc --combined-json abi': the contracts by `<file>:<Contract>`, the imported files' ones included.
 #[derive(Deserialize)]
-struct AbiEntry {
-    #[serde(rename = "type")]
-    kind: String,
-    name: Option<String>,
-    inputs: Option<Vec<AbiInput>>,
-    // For events, if not specified we assum false.
-    #[serde(default)]
-    anonymous: bool,
+struct SolcOutput {
+    contracts: BTreeMap<String, SolcContract>,
+}
+
+/// A contract compiled by solc. Its ABI is a JSON string before solc 0.8.10, an array since.
+#[derive(Deserialize)]
+struct SolcContract {
+    abi: serde_json::Value,
 }
 
+/// Compiled artifact written by forge under `out/<File>.sol/<Contract>.json`, only the ABI is needed.
 #[derive(Deserialize)]
-struct AbiInput {
+struct Artifact {
+    abi: Vec<AbiEntry>,
+}
+
+const USAGE: &str = "<contracts_folder_path> [output_folder_path] [options]
+       or: <contracts_folder_path>... --output <output_folder_path> [options]
+       or: --abi <abi_file.json> [--name <ContractName>] [output_folder_path] [options]
+       or: --abi-dir <abi_folder> [output_folder_path] [options]
+       or: --files-from <list|-> [output_folder_path] [options]
+       or: --contract <path:Name>... [output_folder_path] [options]
+       or: --from-bytecode <hexfile> [--name <ContractName>] [output_folder_path] [options]
+       or: --address <0x...> [--etherscan-key <key>] [--chain-id <id>] [--name <ContractName>] [output_folder_path] [options]
+       or: lookup <selector_or_topic> <contracts_folder_path> [options]
+       or: lookup <selector_or_topic> --abi <abi_file.json> [options]
+       or: sig <signature>... [--event]
+Use '-' as output_folder_path (or --stdout) to print the CSV tables instead of writing files.
+Options:
+  --config <path>        read the default options from this file (selector_calculator.toml by default)
+  --output <path>        output folder, every argument being then a contracts' folder (several ones are merged)
+  --format <format>      output files' format: csv (default), json, jsonl (an object per line)
+                         or html (a single report.html)
+                         or topics-only (only the events' topics, in a single topics.csv)
+                         or cast (`signature hash` lines, in a single signatures.txt)
+                         or sqlite (tables updated in place in selectors.db)
+  --db <path>            database written by '--format sqlite' (<output_folder_path>/selectors.db by default)
+  --fail-on-collision    exit with an error if two functions share a selector
+  --strict               exit with an error if two functions of the same contract share a selector
+  --fail-on-empty        exit with an error if no function nor event was found
+  --no-compile           skip 'forge compile' and use the existing artifacts
+  --allow-compile-errors go on with the artifacts that were built when 'forge compile' fails
+  --dry-run              only list the files and contracts that would be processed, without compiling nor writing
+  --from-method-ids      take the functions' selectors from 'forge inspect ... methodIdentifiers'
+  --jobs <N>             number of contracts processed at once (number of CPUs by default)
+  --cache                reuse the ABIs of the unchanged .sol files from .selector_cache.json
+  --watch                run again each time a contract file of the contracts' folder changes
+  --include-vyper        also process the .vy files, with 'vyper -f abi'
+  --use-inspect          get each ABI from 'forge inspect' instead of the compiled artifacts
+  --solc                 get each ABI from 'solc --combined-json abi', for .sol files outside a forge project
+  --since <git-ref>      only process the contract files changed since this commit, with 'git diff'
+  --files-from <list|->  process the files listed in this file ('-' for stdin) instead of a contracts' folder
+  --contract <path:Name> only process this contract, e.g. src/Token.sol:Token (repeatable, implies --use-inspect)
+  --abi-override <Contract>=<path.json>
+                         read this contract's ABI from this file instead of forge's (repeatable)
+  --include <glob>       only process the .sol files matching the glob (repeatable)
+  --exclude <glob>       skip the .sol files matching the glob (repeatable, takes precedence over --include)
+  --max-depth <N>        only look for files N levels deep in the contracts' folder (1: directly in it)
+  --no-default-excludes  also walk the lib, node_modules, out and cache folders, skipped by default
+  --delimiter <char>     separator of the CSV fields, e.g. ';' or '\\t' for tabs (',' by default)
+  --stdout               print the CSV tables to the standard output instead of writing files
+  --txt                  also write a <Contract>.txt per contract in selectors/ and events/
+  --event-layout         also write events/event_layouts.csv, the indexed and data types of each event
+  --stats                also write stats.csv, the number of functions, events and errors of each contract
+  --interface-id         also write interface_ids.csv, the ERC-165 interface ID of each contract's functions
+  --typehash             also write typehashes.csv, the EIP-712 type hash of each struct of the ABIs
+  --topics-layout        add the topic0 to topic3 columns to the events, with the hash and the indexed types
+  --flat                 write the files directly in the output folder, without sub-folders
+  --subdir <kind>=<name> rename a kind's sub-folder, e.g. --subdir selectors=functions (repeatable)
+  --columns <names>      columns of selectors.csv and their order, e.g. contract,selector,signature,mutability
+  --group-by-mutability  list the view and pure functions first, then the nonpayable and payable ones
+  --only <kind>          only compute and write this kind: functions, events, errors or constructors (repeatable)
+  --prefix-filter <0x..> only keep the functions whose selector starts with these hex digits
+  --min-indexed <N>      only keep the events with at least N indexed parameters
+  --combined             single signatures.csv with a 'kind' column instead of a file per kind
+  --no-sort              keep the contracts and their entries in discovery order instead of sorting them
+  --dedup                single list of unique signatures with the contracts defining each one
+  --with-names           add a column with the parameters' names of each function, event and error
+  --with-index           add a column with the position of each function, event and error in the ABI
+  --with-source          add a column with the .sol file of each contract, relative to the contracts' folder
+  --full-hash            add the full keccak256 hash of each function's signature to selectors.csv
+  --no-0x                write the selectors and topics as bare hex digits, without '0x'
+  --hash <algorithm>     hash function of the selectors and topics: keccak256 (default) or sha256
+  --selector-bytes <N>   number of the hash's 1st bytes making a selector (4 by default)
+  --emit-interface       also write a Solidity interface per contract, reconstructed from its ABI
+  --verify               check each function's selector against 'cast sig'
+  --lookup-4byte         add the signatures registered in the 4byte directory for each function's selector
+  --flag-known           flag the functions with a notable selector (ERC-165, receivers, proxy admin, ...)
+  --proxy <Contract>     with '--impl', report the proxy's selectors clashing with the implementation's ones
+  --impl <Contract>      implementation contract behind '--proxy'
+  --diff <old.csv>       compare the functions with a selectors.csv of a previous run, written in diff.csv
+  --fail-on-removed      with '--diff', exit with an error if a function was removed
+  --summary-json <path>  write the number of contracts, functions, events and errors, and the failures
+  --manifest <path>      write the functions by selector, with their parameters' names and types (JSON)
+  --solc-comment         start the CSV files with a '# solc <version>' line, the version used to compile
+  -v, --verbose          print debugging details
+  -q, --quiet            only print errors and the final summary";
+
+/// Folders skipped when walking the contracts' folder: dependencies, artifacts and forge's cache.
+const DEFAULT_EXCLUDES: [&str; 4] = ["lib", "node_modules", "out", "cache"];
+
+/// Kinds of output, each one written in its own sub-folder and file.
+const KINDS: [&str; 4] = ["selectors", "events", "errors", "constructors"];
+
+/// Columns of selectors.csv that '--columns' can pick, as named in its header.
+const SELECTOR_COLUMNS: [&str; 12] = [
+    "contractName",
+    "function",
+    "selector",
+    "stateMutability",
+    "hash",
+    "knownSignatures",
+    "knownAs",
+    "parameterNames",
+    "abiIndex",
+    "source",
+    "repo",
+    "payable",
+];
+
+/// Functions' groups with '--group-by-mutability', in the order they're listed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MutabilityGroup {
+    // view and pure: reading only.
+    Read,
+    // nonpayable, also when the ABI doesn't say.
+    NonPayable,
+    Payable,
+}
+
+impl MutabilityGroup {
+    fn of(state_mutability: Option<&str>) -> Self {
+        match state_mutability {
+            Some("view" | "pure") => MutabilityGroup::Read,
+            Some("payable") => MutabilityGroup::Payable,
+            _ => MutabilityGroup::NonPayable,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            MutabilityGroup::Read => "view and pure",
+            MutabilityGroup::NonPayable => "nonpayable",
+            MutabilityGroup::Payable => "payable",
+        }
+    }
+}
+
+/// Output format of the generated files.
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputFormat {
+    Csv,
+    Json,
+    // An object per line, with sorted keys, for reports kept under version control.
+    Jsonl,
+    // Single report with every contract.
+    Html,
+    // Single list of the events' topics, for log filters.
+    #[serde(rename = "topics-only")]
+    TopicsOnly,
+    // Single list of `signature hash` lines, like the ones of 'cast sig'.
+    Cast,
+    // Tables upserted in a database, see '--db'.
+    Sqlite,
+}
+
+impl OutputFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Csv | OutputFormat::TopicsOnly => "csv",
+            OutputFormat::Json => "json",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Html => "html",
+            OutputFormat::Cast => "txt",
+            OutputFormat::Sqlite => "db",
+        }
+    }
+}
+
+/// Options taken from terminal commands.
+struct Options {
+    // Folders walked for contracts, several ones being merged. None when reading a raw ABI file.
+    contracts_paths: Vec<String>,
+    // Forge project of each contracts' folder, where it's compiled and its artifacts are. Only looked for
+    // with several folders, the current folder is the project otherwise.
+    project_roots: Vec<PathBuf>,
+    output_dir: String,
+    // Print to the standard output instead of writing files.
+    stdout: bool,
+    format: OutputFormat,
+    // SQLite database of '--format sqlite', under the output folder by default.
+    db: Option<String>,
+    fail_on_collision: bool,
+    // A contract with two functions sharing a selector is an error, not a warning.
+    strict: bool,
+    // A run finding nothing is an error, e.g. a wrong path in CI.
+    fail_on_empty: bool,
+    // Reuse the artifacts already under 'out/'.
+    no_compile: bool,
+    // Go on with the artifacts that were built when 'forge compile' fails.
+    allow_compile_errors: bool,
+    // List the files and contracts found, and stop.
+    dry_run: bool,
+    use_inspect: bool,
+    // ABIs compiled by solc instead of forge, without a forge project.
+    solc: bool,
+    // Also look for .vy files, their ABI given by the Vyper compiler.
+    include_vyper: bool,
+    // Threads processing the contracts, rayon's default (number of CPUs) if not given.
+    jobs: Option<usize>,
+    // Reuse the ABIs of unchanged files from the cache file.
+    cache: bool,
+    // Run again on each change of the contracts' files.
+    watch: bool,
+    // Functions' selectors given by the compiler instead of computed.
+    from_method_ids: bool,
+    abi_path: Option<String>,
+    // Folder of raw ABI files, a contract per file.
+    abi_dir: Option<String>,
+    // Runtime bytecode whose dispatcher gives the functions' selectors, without signatures.
+    from_bytecode: Option<String>,
+    // Files to process instead of walking the contracts' folder, read from stdin if '-'.
+    files_from: Option<String>,
+    // Git commit whose changed contract files are the only ones processed.
+    since: Option<String>,
+    // Contracts given by their fully qualified name ('<path>:<Name>'), processed instead of a contracts' folder.
+    qualified_names: Vec<(PathBuf, String)>,
+    // ABI files read instead of forge's ABI, by contract's name.
+    abi_overrides: BTreeMap<String, PathBuf>,
+    // Verified on-chain contract, its ABI fetched from Etherscan.
+    address: Option<String>,
+    etherscan_key: Option<String>,
+    chain_id: u64,
+    contract_name: Option<String>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    // Depth of the walk, 1 being the files directly in the contracts' folder. No limit if not given.
+    max_depth: Option<usize>,
+    // Also walk the vendored folders (DEFAULT_EXCLUDES).
+    no_default_excludes: bool,
+    // Individual .txt files per contract.
+    txt: bool,
+    // Indexed and data types of each event.
+    event_layout: bool,
+    // Content of each topic of the events' logs.
+    topics_layout: bool,
+    // Number of functions, events and errors of each contract.
+    stats: bool,
+    // ERC-165 interface ID of each contract, the XOR of its functions' selectors.
+    interface_id: bool,
+    // EIP-712 type hash of each struct of the ABIs.
+    typehash: bool,
+    // No sub-folder per kind.
+    flat: bool,
+    // Sub-folders renamed with '--subdir', as (kind, name).
+    subdirs: Vec<(String, String)>,
+    // Separator of the CSV files' fields, ',' by default.
+    delimiter: u8,
+    // Single file with every kind instead of a file per kind.
+    combined: bool,
+    dedup: bool,
+    // Keep the discovery order.
+    no_sort: bool,
+    // Functions grouped by state mutability, with a comment row before each group.
+    group_by_mutability: bool,
+    // Columns of selectors.csv in their order with '--columns', all of them if empty.
+    columns: Vec<String>,
+    // Kinds computed and written with '--only', all of them if empty.
+    only: Vec<String>,
+    // Lowercase '0x'-prefixed start of the functions' selectors to keep.
+    prefix_filter: Option<String>,
+    // Fewest indexed parameters of the events to keep, 0 keeps them all.
+    min_indexed: usize,
+    // Parameters' names column.
+    with_names: bool,
+    // Position in the ABI column.
+    with_index: bool,
+    // Contract's file column.
+    with_source: bool,
+    full_hash: bool,
+    // Selectors and topics written without their '0x' prefix.
+    no_0x: bool,
+    // Hash function and selectors' length, the EVM's ones by default.
+    hasher: SelectorHasher,
+    emit_interface: bool,
+    // Compare the selectors with Foundry's 'cast sig'.
+    verify: bool,
+    lookup_4byte: bool,
+    // Annotate (and warn about clashes with) the notable selectors.
+    flag_known: bool,
+    // 'lookup' subcommand: print only what matches this selector or 32 bytes topic.
+    lookup: Option<String>,
+    // Proxy and implementation contracts whose selectors must not clash.
+    proxy: Option<String>,
+    implementation: Option<String>,
+    // Previous selectors.csv to compare with.
+    diff: Option<String>,
+    fail_on_removed: bool,
+    // Counts and failures of the run, for scripts.
+    summary_json: Option<String>,
+    // Functions by selector with their parameters, for documentation generators.
+    manifest: Option<String>,
+    // '# solc <version>' line at the top of the CSV files.
+    solc_comment: bool,
+    log_level: LevelFilter,
+}
+
+impl Options {
+    /// Whether a kind (e.g. 'events') is computed and written, see '--only'.
+    fn wants(&self, kind: &str) -> bool {
+        self.only.is_empty() || self.only.iter().any(|k| k == kind)
+    }
+
+    /// Whether several contracts' folders are merged, each entry being labelled with its folder.
+    fn merges_folders(&self) -> bool {
+        self.contracts_paths.len() > 1
+    }
+}
+
+/// Include/exclude globs applied to the .sol files' paths. Excludes take precedence over includes,
+/// and without includes every file not excluded is processed.
+struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    fn new(include: &[String], exclude: &[String]) -> Result<Self, globset::Error> {
+        let include = if include.is_empty() { None } else { Some(build_globset(include)?) };
+        Ok(PathFilter { include, exclude: build_globset(exclude)? })
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        if self.exclude.is_match(path) {
+            return false;
+        }
+        self.include.as_ref().map(|include| include.is_match(path)).unwrap_or(true)
+    }
+}
+
+fn build_globset(patterns: &[String]) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    builder.build()
+}
+
+/// A function with its 4 bytes selector.
+#[derive(Serialize)]
+struct FunctionRow {
+    contract: String,
+    signature: String,
+    selector: String,
+    #[serde(rename = "stateMutability")]
+    state_mutability: Option<String>,
+    // Full keccak256 hash of the signature, only with '--full-hash'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+    // Signatures registered in the 4byte directory, only with '--lookup-4byte'.
+    #[serde(rename = "knownSignatures", skip_serializing_if = "Option::is_none")]
+    known_signatures: Option<String>,
+    // Notable selector it is, e.g. `ERC-165 supportsInterface(bytes4)`, only with '--flag-known'.
+    #[serde(rename = "knownAs", skip_serializing_if = "Option::is_none")]
+    known_as: Option<String>,
+    // Parameters' names, only with '--with-names'.
+    #[serde(rename = "parameterNames", skip_serializing_if = "Option::is_none")]
+    names: Option<String>,
+    // Zero-based position of the entry in the contract's ABI, only with '--with-index'.
+    #[serde(rename = "abiIndex", skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    // Contract's file, relative to the contracts' folder, only with '--with-source'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    // Contracts' folder of the contract, only when several ones are merged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repo: Option<String>,
+    // Parameters' names and types, only kept with '--manifest'.
+    #[serde(skip)]
+    inputs: Vec<Parameter>,
+    // Can receive ETH, from the state mutability.
+    payable: bool,
+}
+
+/// A function's parameter in the manifest ('--manifest').
+#[derive(Serialize)]
+struct Parameter {
+    name: String,
     #[serde(rename = "type")]
     param_type: String,
-    #[serde(default)]
-    components: Option<Vec<AbiInput>>,
+    // Leaf fields of a struct with their dotted names, with '--with-names'.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<Parameter>,
 }
 
-fn process_type_with_components(input: &AbiInput) -> String {
-    match input.param_type.as_str() {
-        "tuple[]" => {
-            if let Some(components) = &input.components {
-                let component_types: Vec<String> = components
-                    .iter()
-                    .map(|c| process_type_with_components(c))
-                    .collect();
-                format!("({})[]", component_types.join(","))
-            } else {
-                "tuple[]".to_string()
+/// An error with its 4 bytes selector.
+#[derive(Serialize)]
+struct ErrorRow {
+    contract: String,
+    signature: String,
+    selector: String,
+    // Parameters' names, only with '--with-names'.
+    #[serde(rename = "parameterNames", skip_serializing_if = "Option::is_none")]
+    names: Option<String>,
+    // Zero-based position of the entry in the contract's ABI, only with '--with-index'.
+    #[serde(rename = "abiIndex", skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    // Contract's file, relative to the contracts' folder, only with '--with-source'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    // Contracts' folder of the contract, only when several ones are merged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repo: Option<String>,
+}
+
+/// A constructor's pseudo-signature, it has no selector.
+#[derive(Serialize)]
+struct ConstructorRow {
+    contract: String,
+    signature: String,
+    // Contract's file, relative to the contracts' folder, only with '--with-source'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    // Contracts' folder of the contract, only when several ones are merged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repo: Option<String>,
+}
+
+/// An event with its topic and the positions of its indexed parameters.
+#[derive(Serialize)]
+struct EventRow {
+    contract: String,
+    signature: String,
+    // Empty for anonymous events, their logs have no topic0.
+    topic: String,
+    indexed: Vec<usize>,
+    anonymous: bool,
+    // Types of the indexed parameters, then of the other ones, for '--event-layout'.
+    #[serde(skip)]
+    indexed_types: Vec<String>,
+    #[serde(skip)]
+    data_types: Vec<String>,
+    // Content of the topic0 to topic3 of its logs, only with '--topics-layout'.
+    #[serde(rename = "topics", skip_serializing_if = "Option::is_none")]
+    topics_layout: Option<[String; 4]>,
+    // Parameters' names, only with '--with-names'.
+    #[serde(rename = "parameterNames", skip_serializing_if = "Option::is_none")]
+    names: Option<String>,
+    // Zero-based position of the entry in the contract's ABI, only with '--with-index'.
+    #[serde(rename = "abiIndex", skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    // Contract's file, relative to the contracts' folder, only with '--with-source'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    // Contracts' folder of the contract, only when several ones are merged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repo: Option<String>,
+}
+
+/// Everything computed for a single contract.
+struct ContractData {
+    name: String,
+    functions: Vec<FunctionRow>,
+    events: Vec<EventRow>,
+    errors: Vec<ErrorRow>,
+    constructors: Vec<ConstructorRow>,
+    // Solidity interface reconstructed from the ABI, only with '--emit-interface'.
+    interface: Option<String>,
+    // EIP-712 encoded type of each struct of the ABI as (name, encoded type), only with '--typehash'.
+    struct_types: Vec<(String, String)>,
+}
+
+impl ContractData {
+    /// Nothing in the ABI, e.g. a library or an interface-only file.
+    fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.events.is_empty() && self.errors.is_empty() && self.constructors.is_empty()
+    }
+
+    /// Different functions sharing a selector, as `(selector, signatureA, signatureB)`. Solidity rejects them, only
+    /// a malformed ABI has some. Each one is paired with the first function seen with its selector.
+    fn duplicate_selectors(&self) -> Vec<(&str, &str, &str)> {
+        let mut first_seen: HashMap<&str, &str> = HashMap::new();
+        let mut duplicates = Vec::new();
+        for f in self.functions.iter().filter(|f| !f.selector.is_empty()) {
+            match first_seen.get(f.selector.as_str()) {
+                Some(signature) if *signature != f.signature => {
+                    duplicates.push((f.selector.as_str(), *signature, f.signature.as_str()))
+                }
+                Some(_) => {}
+                None => {
+                    first_seen.insert(&f.selector, &f.signature);
+                }
+            }
+        }
+        duplicates
+    }
+
+    /// Drop the repeated entries (same signature and hash), keeping the first one. Inherited and
+    /// overridden functions sometimes appear twice in the ABI given by forge.
+    fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.functions.retain(|f| seen.insert((f.signature.clone(), f.selector.clone())));
+        seen.clear();
+        self.events.retain(|e| seen.insert((e.signature.clone(), e.topic.clone())));
+        seen.clear();
+        self.errors.retain(|e| seen.insert((e.signature.clone(), e.selector.clone())));
+    }
+
+    /// Put the view and pure functions first, then the nonpayable and the payable ones, keeping their order within
+    /// each group.
+    fn group_functions(&mut self) {
+        self.functions.sort_by_key(|f| MutabilityGroup::of(f.state_mutability.as_deref()));
+    }
+
+    /// Drop the `0x` prefix of the selectors, topics and full hashes ('--no-0x').
+    fn strip_hex_prefix(&mut self) {
+        let strip = |hash: &mut String| {
+            if let Some(digits) = hash.strip_prefix("0x") {
+                *hash = digits.to_string();
+            }
+        };
+        for f in &mut self.functions {
+            strip(&mut f.selector);
+            if let Some(hash) = &mut f.hash {
+                strip(hash);
+            }
+        }
+        for e in &mut self.events {
+            strip(&mut e.topic);
+            if let Some([topic0, ..]) = &mut e.topics_layout {
+                strip(topic0);
+            }
+        }
+        for e in &mut self.errors {
+            strip(&mut e.selector);
+        }
+    }
+
+    /// Change the contract's name, in its rows too.
+    fn rename(&mut self, name: String) {
+        for f in &mut self.functions {
+            f.contract = name.clone();
+        }
+        for e in &mut self.events {
+            e.contract = name.clone();
+        }
+        for e in &mut self.errors {
+            e.contract = name.clone();
+        }
+        for c in &mut self.constructors {
+            c.contract = name.clone();
+        }
+        self.name = name;
+    }
+
+    /// Set the file of every row.
+    fn set_source(&mut self, source: &str) {
+        let source = Some(source.to_string());
+        self.functions.iter_mut().for_each(|f| f.source = source.clone());
+        self.events.iter_mut().for_each(|e| e.source = source.clone());
+        self.errors.iter_mut().for_each(|e| e.source = source.clone());
+        self.constructors.iter_mut().for_each(|c| c.source = source.clone());
+    }
+
+    /// Label every entry with the contracts' folder the contract was found in.
+    fn set_repo(&mut self, repo: &str) {
+        let repo = Some(repo.to_string());
+        self.functions.iter_mut().for_each(|f| f.repo = repo.clone());
+        self.events.iter_mut().for_each(|e| e.repo = repo.clone());
+        self.errors.iter_mut().for_each(|e| e.repo = repo.clone());
+        self.constructors.iter_mut().for_each(|c| c.repo = repo.clone());
+    }
+
+    /// Sort each kind of entry by signature.
+    fn sort(&mut self) {
+        self.functions.sort_by(|a, b| a.signature.cmp(&b.signature));
+        self.events.sort_by(|a, b| a.signature.cmp(&b.signature));
+        self.errors.sort_by(|a, b| a.signature.cmp(&b.signature));
+        self.constructors.sort_by(|a, b| a.signature.cmp(&b.signature));
+    }
+}
+
+/// A .sol file (or one of its contracts) that couldn't be processed.
+#[derive(Serialize)]
+struct Failure {
+    path: PathBuf,
+    error: String,
+}
+
+impl Failure {
+    /// Log the error and keep it for the summary.
+    fn new(path: &Path, err: anyhow::Error) -> Self {
+        warn!("{:#}", err);
+        Failure { path: path.to_path_buf(), error: format!("{:#}", err) }
+    }
+}
+
+/// Normalize the hash given to 'lookup' as lowercase `0x` hex, checking it's a selector or a topic.
+fn parse_lookup_hash(hash: &str, selector_bytes: usize) -> Result<String, String> {
+    let digits = hash.strip_prefix("0x").or_else(|| hash.strip_prefix("0X")).unwrap_or(hash);
+    let bytes = hex::decode(digits).map_err(|err| format!("Invalid hex '{}': {}", hash, err))?;
+    if bytes.len() != selector_bytes && bytes.len() != 32 {
+        return Err(format!(
+            "'{}' is {} bytes long, expected a {} bytes selector or a 32 bytes topic",
+            hash,
+            bytes.len(),
+            selector_bytes
+        ));
+    }
+    Ok(format!("0x{}", hex::encode(bytes)))
+}
+
+/// Check the hex digits given to '--prefix-filter', at most a selector long, returned lowercase with '0x'.
+fn parse_selector_prefix(prefix: &str, selector_bytes: usize) -> Result<String, String> {
+    let digits = prefix.strip_prefix("0x").or_else(|| prefix.strip_prefix("0X")).unwrap_or(prefix);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid prefix '{}' for '--prefix-filter', expected hex digits e.g. 0x0000", prefix));
+    }
+    if digits.len() > 2 * selector_bytes {
+        return Err(format!("Prefix '{}' is longer than a {} bytes selector", prefix, selector_bytes));
+    }
+    Ok(format!("0x{}", digits.to_ascii_lowercase()))
+}
+
+/// Separator of '--delimiter': a single ASCII character, `\t` being a tab. Quotes and line breaks would make
+/// the fields unreadable.
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    let delimiter = if value == "\\t" { "\t" } else { value };
+    match delimiter.as_bytes() {
+        [b'"' | b'\n' | b'\r'] => Err(format!("'{}' can't be the CSV delimiter", value.escape_default())),
+        [byte] => Ok(*byte),
+        _ => Err(format!("Invalid delimiter '{}', expected a single ASCII character", value)),
+    }
+}
+
+/// Columns of a '--columns' value, e.g. `contract,selector,signature`, as named in selectors.csv's header.
+fn parse_columns(value: &str) -> Result<Vec<String>, String> {
+    let mut columns: Vec<String> = Vec::new();
+    for name in value.split(',').map(str::trim) {
+        // Shorter names of the columns always written
+        let column = match name {
+            "contract" => "contractName",
+            "signature" => "function",
+            "mutability" => "stateMutability",
+            other => other,
+        };
+        if !SELECTOR_COLUMNS.contains(&column) {
+            return Err(format!(
+                "Unknown column '{}', expected contract, signature, mutability or one of: {}",
+                name,
+                SELECTOR_COLUMNS.join(", ")
+            ));
+        }
+        if columns.iter().any(|c| c == column) {
+            return Err(format!("Column '{}' is given twice in '--columns'", name));
+        }
+        columns.push(column.to_string());
+    }
+    Ok(columns)
+}
+
+/// Split an '--abi-override' value, e.g. `Token=abis/Token.json`, into the contract's name and the ABI file.
+fn parse_abi_override(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((name, path)) if !name.is_empty() && !path.is_empty() => Ok((name.to_string(), PathBuf::from(path))),
+        _ => Err(format!("Invalid ABI override '{}', expected '<ContractName>=<path.json>'", value)),
+    }
+}
+
+/// Split a contract's fully qualified name given to '--contract', e.g. `src/Token.sol:Token`, into its file and name.
+fn parse_qualified_name(qualified_name: &str) -> Result<(PathBuf, String), String> {
+    match qualified_name.rsplit_once(':') {
+        Some((path, name)) if !path.is_empty() && !name.is_empty() => Ok((PathBuf::from(path), name.to_string())),
+        _ => Err(format!(
+            "Invalid contract '{}', expected '<path>:<ContractName>', e.g. 'src/Token.sol:Token'",
+            qualified_name
+        )),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut positional: Vec<&String> = Vec::new();
+    let mut output = None;
+    let mut config_path = None;
+    let mut format = None;
+    let mut db = None;
+    let mut fail_on_collision = false;
+    let mut strict = false;
+    let mut fail_on_empty = false;
+    let mut no_compile = false;
+    let mut allow_compile_errors = false;
+    let mut dry_run = false;
+    let mut use_inspect = false;
+    let mut solc = false;
+    let mut include_vyper = false;
+    let mut jobs = None;
+    let mut cache = false;
+    let mut watch = false;
+    let mut from_method_ids = false;
+    let mut abi_path = None;
+    let mut abi_dir = None;
+    let mut from_bytecode = None;
+    let mut files_from = None;
+    let mut since = None;
+    let mut qualified_names = Vec::new();
+    let mut abi_overrides = BTreeMap::new();
+    let mut address = None;
+    let mut etherscan_key = None;
+    let mut chain_id = 1;
+    let mut contract_name = None;
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    let mut max_depth = None;
+    let mut no_default_excludes = false;
+    let mut txt = false;
+    let mut event_layout = false;
+    let mut stats = false;
+    let mut interface_id = false;
+    let mut typehash = false;
+    let mut topics_layout = false;
+    let mut flat = false;
+    let mut subdirs = Vec::new();
+    let mut delimiter = b',';
+    let mut combined = false;
+    let mut dedup = false;
+    let mut no_sort = false;
+    let mut columns = Vec::new();
+    let mut group_by_mutability = false;
+    let mut only = Vec::new();
+    let mut prefix_filter = None;
+    let mut min_indexed = 0;
+    let mut stdout = false;
+    let mut with_names = false;
+    let mut with_index = false;
+    let mut with_source = false;
+    let mut full_hash = false;
+    let mut no_0x = false;
+    let mut hasher = SelectorHasher::default();
+    let mut emit_interface = false;
+    let mut verify = false;
+    let mut lookup_4byte = false;
+    let mut flag_known = false;
+    let mut proxy = None;
+    let mut implementation = None;
+    let mut diff = None;
+    let mut fail_on_removed = false;
+    let mut summary_json = None;
+    let mut manifest = None;
+    let mut solc_comment = false;
+    let mut log_level = LevelFilter::Info;
+
+    let mut iter = args.iter().skip(1).peekable();
+    let lookup = if iter.next_if(|arg| arg.as_str() == "lookup").is_some() {
+        Some(iter.next().ok_or("'lookup' requires a selector or topic")?)
+    } else {
+        None
+    };
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => config_path = Some(iter.next().ok_or("'--config' requires a value")?.clone()),
+            "--format" => {
+                let value = iter.next().ok_or("'--format' requires a value")?;
+                format = Some(match value.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    "jsonl" => OutputFormat::Jsonl,
+                    "html" => OutputFormat::Html,
+                    "topics-only" => OutputFormat::TopicsOnly,
+                    "cast" => OutputFormat::Cast,
+                    "sqlite" => OutputFormat::Sqlite,
+                    other => {
+                        return Err(format!(
+                            "Unknown format '{}', expected 'csv', 'json', 'jsonl', 'html', 'topics-only', 'cast' \
+                             or 'sqlite'",
+                            other
+                        ))
+                    }
+                });
+            }
+            "--db" => db = Some(iter.next().ok_or("'--db' requires a value")?.clone()),
+            "--fail-on-collision" => fail_on_collision = true,
+            "--strict" => strict = true,
+            "--fail-on-empty" => fail_on_empty = true,
+            "--no-compile" => no_compile = true,
+            "--allow-compile-errors" => allow_compile_errors = true,
+            "--dry-run" => dry_run = true,
+            "--use-inspect" => use_inspect = true,
+            "--solc" => solc = true,
+            "--include-vyper" => include_vyper = true,
+            "--jobs" => {
+                let value = iter.next().ok_or("'--jobs' requires a value")?;
+                match value.parse::<usize>() {
+                    Ok(n) if n > 0 => jobs = Some(n),
+                    _ => return Err(format!("Invalid number of jobs '{}'", value)),
+                }
+            }
+            "--cache" => cache = true,
+            "--watch" => watch = true,
+            "--from-method-ids" => from_method_ids = true,
+            "--abi" => abi_path = Some(iter.next().ok_or("'--abi' requires a value")?.clone()),
+            "--abi-dir" => abi_dir = Some(iter.next().ok_or("'--abi-dir' requires a value")?.clone()),
+            "--from-bytecode" => from_bytecode = Some(iter.next().ok_or("'--from-bytecode' requires a value")?.clone()),
+            "--output" => output = Some(iter.next().ok_or("'--output' requires a value")?),
+            "--since" => since = Some(iter.next().ok_or("'--since' requires a value")?.clone()),
+            "--files-from" => files_from = Some(iter.next().ok_or("'--files-from' requires a value")?.clone()),
+            "--contract" => {
+                qualified_names.push(parse_qualified_name(iter.next().ok_or("'--contract' requires a value")?)?)
+            }
+            "--abi-override" => {
+                let (name, path) = parse_abi_override(iter.next().ok_or("'--abi-override' requires a value")?)?;
+                if abi_overrides.insert(name.clone(), path).is_some() {
+                    return Err(format!("Several ABI overrides for '{}'", name));
+                }
+            }
+            "--address" => {
+                let value = iter.next().ok_or("'--address' requires a value")?;
+                let digits = value.strip_prefix("0x").unwrap_or(value);
+                if digits.len() != 40 || hex::decode(digits).is_err() {
+                    return Err(format!("Invalid address '{}'", value));
+                }
+                address = Some(format!("0x{}", digits));
+            }
+            "--etherscan-key" => etherscan_key = Some(iter.next().ok_or("'--etherscan-key' requires a value")?.clone()),
+            "--chain-id" => {
+                let value = iter.next().ok_or("'--chain-id' requires a value")?;
+                chain_id = value.parse().map_err(|_| format!("Invalid chain id '{}'", value))?;
             }
+            "--name" => contract_name = Some(iter.next().ok_or("'--name' requires a value")?.clone()),
+            "--include" => include.push(iter.next().ok_or("'--include' requires a value")?.clone()),
+            "--exclude" => exclude.push(iter.next().ok_or("'--exclude' requires a value")?.clone()),
+            "--max-depth" => {
+                let value = iter.next().ok_or("'--max-depth' requires a value")?;
+                match value.parse::<usize>() {
+                    Ok(n) if n > 0 => max_depth = Some(n),
+                    _ => return Err(format!("Invalid depth '{}'", value)),
+                }
+            }
+            "--no-default-excludes" => no_default_excludes = true,
+            "--txt" => txt = true,
+            "--event-layout" => event_layout = true,
+            "--stats" => stats = true,
+            "--interface-id" => interface_id = true,
+            "--typehash" => typehash = true,
+            "--topics-layout" => topics_layout = true,
+            "--flat" => flat = true,
+            "--subdir" => {
+                let value = iter.next().ok_or("'--subdir' requires a value")?;
+                let (kind, name) = value
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid sub-folder '{}', expected <kind>=<name>", value))?;
+                if !KINDS.contains(&kind) {
+                    return Err(format!("Unknown kind '{}', expected one of: {}", kind, KINDS.join(", ")));
+                }
+                subdirs.push((kind.to_string(), name.to_string()));
+            }
+            "--columns" => columns = parse_columns(iter.next().ok_or("'--columns' requires a value")?)?,
+            "--group-by-mutability" => group_by_mutability = true,
+            "--only" => {
+                let value = iter.next().ok_or("'--only' requires a value")?;
+                // Functions are written in 'selectors'
+                let kind = if value == "functions" { "selectors" } else { value.as_str() };
+                if !KINDS.contains(&kind) {
+                    return Err(format!("Unknown kind '{}', expected functions or one of: {}", value, KINDS.join(", ")));
+                }
+                only.push(kind.to_string());
+            }
+            "--prefix-filter" => prefix_filter = Some(iter.next().ok_or("'--prefix-filter' requires a value")?),
+            "--min-indexed" => {
+                let value = iter.next().ok_or("'--min-indexed' requires a value")?;
+                // Anonymous events can have 4 indexed parameters
+                match value.parse::<usize>() {
+                    Ok(n) if n <= 4 => min_indexed = n,
+                    _ => return Err(format!("Invalid number of indexed parameters '{}', expected 0 to 4", value)),
+                }
+            }
+            "--delimiter" => delimiter = parse_delimiter(iter.next().ok_or("'--delimiter' requires a value")?)?,
+            "--combined" => combined = true,
+            "--dedup" => dedup = true,
+            "--no-sort" => no_sort = true,
+            "--stdout" => stdout = true,
+            "--with-names" => with_names = true,
+            "--with-index" => with_index = true,
+            "--with-source" => with_source = true,
+            "--full-hash" => full_hash = true,
+            "--no-0x" => no_0x = true,
+            "--hash" => {
+                let value = iter.next().ok_or("'--hash' requires a value")?;
+                hasher.algorithm = HashAlgorithm::from_name(value)
+                    .ok_or_else(|| format!("Unknown hash '{}', expected 'keccak256' or 'sha256'", value))?;
+            }
+            "--selector-bytes" => {
+                let value = iter.next().ok_or("'--selector-bytes' requires a value")?;
+                let invalid = || format!("Invalid value '{}' for '--selector-bytes', expected 1 to 32", value);
+                let selector_bytes = value.parse().map_err(|_| invalid())?;
+                hasher = SelectorHasher::new(hasher.algorithm, selector_bytes).map_err(|_| invalid())?;
+            }
+            "--emit-interface" => emit_interface = true,
+            "--verify" => verify = true,
+            "--lookup-4byte" => lookup_4byte = true,
+            "--flag-known" => flag_known = true,
+            "--proxy" => proxy = Some(iter.next().ok_or("'--proxy' requires a value")?.clone()),
+            "--impl" => implementation = Some(iter.next().ok_or("'--impl' requires a value")?.clone()),
+            "--diff" => diff = Some(iter.next().ok_or("'--diff' requires a value")?.clone()),
+            "--fail-on-removed" => fail_on_removed = true,
+            "--summary-json" => summary_json = Some(iter.next().ok_or("'--summary-json' requires a value")?.clone()),
+            "--manifest" => manifest = Some(iter.next().ok_or("'--manifest' requires a value")?.clone()),
+            "--solc-comment" => solc_comment = true,
+            "-v" | "--verbose" => log_level = LevelFilter::Debug,
+            "-q" | "--quiet" => log_level = LevelFilter::Error,
+            flag if flag.starts_with('-') && flag.len() > 1 => return Err(format!("Unknown option '{}'", flag)),
+            _ => positional.push(arg),
         }
-        "tuple" => {
-            if let Some(components) = &input.components {
-                let component_types: Vec<String> = components
+    }
+
+    if [abi_path.is_some(), address.is_some(), from_bytecode.is_some()].into_iter().filter(|given| *given).count() > 1 {
+        return Err("'--abi', '--address' and '--from-bytecode' can't be used together".to_string());
+    }
+    let single_abi = abi_path.is_some() || address.is_some() || from_bytecode.is_some();
+    if single_abi && files_from.is_some() {
+        return Err("'--files-from' can't be used with '--abi', '--address' or '--from-bytecode'".to_string());
+    }
+    if !qualified_names.is_empty() && (single_abi || files_from.is_some()) {
+        return Err("'--contract' can't be used with '--abi', '--address', '--from-bytecode' or '--files-from'".to_string());
+    }
+    // The cache keeps every contract of a file, not only the selected ones
+    if !qualified_names.is_empty() && cache {
+        return Err("'--contract' can't be used with '--cache'".to_string());
+    }
+    // The cache would keep the override's ABI as the file's one
+    if !abi_overrides.is_empty() && (single_abi || cache) {
+        return Err(
+            "'--abi-override' can't be used with '--cache', '--abi', '--address' or '--from-bytecode'".to_string()
+        );
+    }
+    // Functions have no signature
+    if from_bytecode.is_some() && (verify || full_hash || emit_interface || typehash) {
+        return Err("'--verify', '--full-hash', '--emit-interface' and '--typehash' need signatures, they can't be used \
+             with '--from-bytecode'"
+            .to_string());
+    }
+    if abi_dir.is_some()
+        && (single_abi
+            || files_from.is_some()
+            || !qualified_names.is_empty()
+            || !abi_overrides.is_empty()
+            || cache
+            || solc
+            || use_inspect
+            || from_method_ids
+            || allow_compile_errors
+            || with_source
+            || dry_run)
+    {
+        return Err("'--abi-dir' reads ABI files without forge, it can't be used with a single contract, \
+             '--files-from', '--contract', '--abi-override', '--cache', '--solc', '--use-inspect', \
+             '--from-method-ids', '--allow-compile-errors', '--with-source' or '--dry-run'"
+            .to_string());
+    }
+    // With a single contract, '--abi-dir', '--files-from' or '--contract' there is no contracts' folder, only the
+    // optional output's folder.
+    let no_folder = single_abi || abi_dir.is_some() || files_from.is_some() || !qualified_names.is_empty();
+    // With '--output', the arguments are all contracts' folders.
+    let (contracts_paths, output_dir) = match (no_folder, output, positional.as_slice()) {
+        (true, None, []) => (Vec::new(), None),
+        (true, None, [output]) => (Vec::new(), Some(*output)),
+        (true, Some(output), []) => (Vec::new(), Some(output)),
+        (false, None, [contracts]) => (vec![*contracts], None),
+        (false, None, [contracts, output]) => (vec![*contracts], Some(*output)),
+        (false, Some(output), [_, ..]) => (positional.clone(), Some(output)),
+        _ => return Err("Wrong number of arguments".to_string()),
+    };
+    let project_roots = if contracts_paths.len() > 1 {
+        contracts_paths.iter().map(|folder| find_project_root(Path::new(folder))).collect()
+    } else {
+        vec![PathBuf::new(); contracts_paths.len()]
+    };
+    // Options given in the command line take precedence over the config file.
+    let config = config::load(config_path.as_deref())?;
+    let format = format.or(config.format).unwrap_or(OutputFormat::Csv);
+    let output_dir = output_dir.map(|o| o.to_string()).or(config.output_dir);
+    if include.is_empty() {
+        include = config.include;
+    }
+    if exclude.is_empty() {
+        exclude = config.exclude;
+    }
+    let dedup = dedup || config.dedup;
+
+    if contract_name.is_some() && !single_abi {
+        return Err("'--name' can only be used with '--abi', '--address' or '--from-bytecode'".to_string());
+    }
+    if solc && (single_abi || use_inspect || from_method_ids || !qualified_names.is_empty()) {
+        return Err("'--solc' replaces forge, it can't be used with a single contract, '--use-inspect', \
+             '--from-method-ids' or '--contract'"
+            .to_string());
+    }
+    if from_method_ids && single_abi {
+        return Err(
+            "'--from-method-ids' needs a forge project, it can't be used with '--abi', '--address' or '--from-bytecode'"
+                .to_string(),
+        );
+    }
+    let stdout = stdout || output_dir.as_deref() == Some("-");
+    if stdout && !matches!(format, OutputFormat::Csv | OutputFormat::TopicsOnly | OutputFormat::Cast) {
+        return Err("Only the csv, topics-only and cast formats can be printed to the standard output".to_string());
+    }
+    if event_layout && !only.is_empty() && !only.iter().any(|kind| kind == "events") {
+        return Err("'--event-layout' needs the events, add '--only events'".to_string());
+    }
+    if topics_layout && !only.is_empty() && !only.iter().any(|kind| kind == "events") {
+        return Err("'--topics-layout' needs the events, add '--only events'".to_string());
+    }
+    if stdout && txt {
+        return Err("'--txt' writes files, it can't be used with '--stdout'".to_string());
+    }
+    // Each kind's .txt files have the same names, they need their own folder
+    if flat && txt {
+        return Err("'--txt' can't be used with '--flat'".to_string());
+    }
+    if flat && !subdirs.is_empty() {
+        return Err("'--subdir' can't be used with '--flat'".to_string());
+    }
+    if stdout && emit_interface {
+        return Err("'--emit-interface' writes files, it can't be used with '--stdout'".to_string());
+    }
+    if format == OutputFormat::Html && (combined || dedup) {
+        return Err("The html report can't be used with '--combined' or '--dedup'".to_string());
+    }
+    if format == OutputFormat::TopicsOnly && (combined || dedup || txt) {
+        return Err("The topics-only format can't be used with '--combined', '--dedup' or '--txt'".to_string());
+    }
+    if format == OutputFormat::Cast && (combined || dedup || txt) {
+        return Err("The cast format can't be used with '--combined', '--dedup' or '--txt'".to_string());
+    }
+    if format == OutputFormat::Sqlite && (combined || dedup || txt) {
+        return Err("The sqlite format can't be used with '--combined', '--dedup' or '--txt'".to_string());
+    }
+    // Its functions are unique by signature, the ones of the bytecode have none
+    if format == OutputFormat::Sqlite && from_bytecode.is_some() {
+        return Err("The sqlite format can't be used with '--from-bytecode'".to_string());
+    }
+    if db.is_some() && format != OutputFormat::Sqlite {
+        return Err("'--db' can only be used with '--format sqlite'".to_string());
+    }
+    if with_source && single_abi {
+        return Err("'--with-source' needs contract files, it can't be used with a single contract".to_string());
+    }
+    let tables_format = matches!(format, OutputFormat::Csv | OutputFormat::Json | OutputFormat::Jsonl);
+    if with_source && (combined || dedup || !tables_format) {
+        return Err("'--with-source' only adds a column to the csv and json tables of each kind, it can't be used \
+             with '--combined', '--dedup' or the other formats"
+            .to_string());
+    }
+    if !columns.is_empty() && (combined || dedup || group_by_mutability || format != OutputFormat::Csv) {
+        return Err("'--columns' is for the csv selectors' table, it can't be used with '--combined', '--dedup', \
+             '--group-by-mutability' or another format"
+            .to_string());
+    }
+    for column in &columns {
+        let needs = match column.as_str() {
+            "hash" => (!full_hash).then_some("'--full-hash'"),
+            "knownSignatures" => (!lookup_4byte).then_some("'--lookup-4byte'"),
+            "knownAs" => (!flag_known).then_some("'--flag-known'"),
+            "parameterNames" => (!with_names).then_some("'--with-names'"),
+            "abiIndex" => (!with_index).then_some("'--with-index'"),
+            "source" => (!with_source).then_some("'--with-source'"),
+            "repo" => (contracts_paths.len() < 2).then_some("several contracts' folders"),
+            _ => None,
+        };
+        if let Some(needs) = needs {
+            return Err(format!("Column '{}' needs {}", column, needs));
+        }
+    }
+    if combined && dedup {
+        return Err("'--combined' can't be used with '--dedup'".to_string());
+    }
+    if proxy.is_some() != implementation.is_some() {
+        return Err("'--proxy' and '--impl' must be used together".to_string());
+    }
+    if since.is_some() && no_folder {
+        return Err("'--since' filters the contracts' folders, it can't be used with a single contract, '--files-from' \
+             or '--contract'"
+            .to_string());
+    }
+    if watch && (no_folder || contracts_paths.len() > 1 || lookup.is_some()) {
+        return Err(
+            "'--watch' needs a single contracts' folder, it can't be used with several ones, a single contract, \
+             '--files-from', '--contract' or 'lookup'"
+                .to_string(),
+        );
+    }
+    if allow_compile_errors && (no_compile || solc || single_abi) {
+        return Err("'--allow-compile-errors' needs 'forge compile', it can't be used with '--no-compile', '--solc' \
+             or a single contract"
+            .to_string());
+    }
+    if dry_run && (single_abi || watch) {
+        return Err("'--dry-run' lists the contract files, it can't be used with a single contract or '--watch'".to_string());
+    }
+    if solc_comment && (stdout || format != OutputFormat::Csv || txt) {
+        return Err("'--solc-comment' is for the CSV files, it can't be used with '--stdout', '--txt' or another format"
+            .to_string());
+    }
+    if fail_on_removed && diff.is_none() {
+        return Err("'--fail-on-removed' can only be used with '--diff'".to_string());
+    }
+    // Those only know the EVM's selectors
+    if !hasher.is_evm() && (verify || lookup_4byte || flag_known || from_method_ids || interface_id) {
+        return Err("'--verify', '--lookup-4byte', '--flag-known', '--from-method-ids' and '--interface-id' can't be \
+             used with '--hash' or '--selector-bytes'"
+            .to_string());
+    }
+    // The ID is of all the contract's functions
+    if interface_id && (prefix_filter.is_some() || (!only.is_empty() && !only.iter().any(|kind| kind == "selectors"))) {
+        return Err("'--interface-id' needs every function, it can't be used with '--prefix-filter' or without \
+             '--only functions'"
+            .to_string());
+    }
+    let lookup = lookup.map(|hash| parse_lookup_hash(hash, hasher.selector_bytes)).transpose()?;
+    let prefix_filter = prefix_filter.map(|prefix| parse_selector_prefix(prefix, hasher.selector_bytes)).transpose()?;
+    Ok(Options {
+        contracts_paths: contracts_paths.into_iter().cloned().collect(),
+        project_roots,
+        output_dir: output_dir.unwrap_or_else(|| "function_selectors".to_string()),
+        stdout,
+        format,
+        db,
+        fail_on_collision,
+        strict,
+        fail_on_empty,
+        no_compile,
+        allow_compile_errors,
+        dry_run,
+        // Only 'forge inspect' tells whether a qualified name exists
+        use_inspect: use_inspect || !qualified_names.is_empty(),
+        solc,
+        include_vyper,
+        jobs,
+        cache,
+        watch,
+        from_method_ids,
+        abi_path,
+        abi_dir,
+        from_bytecode,
+        files_from,
+        since,
+        qualified_names,
+        abi_overrides,
+        address,
+        etherscan_key: etherscan_key.or_else(|| env::var("ETHERSCAN_API_KEY").ok()),
+        chain_id,
+        contract_name,
+        include,
+        exclude,
+        max_depth,
+        no_default_excludes,
+        txt,
+        event_layout,
+        stats,
+        interface_id,
+        typehash,
+        topics_layout,
+        flat,
+        subdirs,
+        delimiter,
+        combined,
+        dedup,
+        no_sort,
+        columns,
+        group_by_mutability,
+        only,
+        prefix_filter,
+        min_indexed,
+        with_names,
+        with_index,
+        with_source,
+        full_hash,
+        no_0x,
+        hasher,
+        emit_interface,
+        verify,
+        lookup_4byte,
+        flag_known,
+        lookup,
+        proxy,
+        implementation,
+        diff,
+        fail_on_removed,
+        summary_json,
+        manifest,
+        solc_comment,
+        log_level,
+    })
+}
+
+/// Folder of a kind: `<output_dir>/<sub-folder>`, or the output folder itself with '--flat'.
+fn kind_dir(output_dir: &Path, kind: &str, options: &Options) -> PathBuf {
+    if options.flat {
+        return output_dir.to_path_buf();
+    }
+    let subdir = options
+        .subdirs
+        .iter()
+        .rev()
+        .find(|(k, _)| k == kind)
+        .map(|(_, name)| name.as_str())
+        .unwrap_or(kind);
+    output_dir.join(subdir)
+}
+
+/// Output file of a kind: `<kind folder>/<kind>.<extension>`.
+fn kind_path(output_dir: &Path, kind: &str, options: &Options) -> PathBuf {
+    kind_dir(output_dir, kind, options).join(format!("{}.{}", kind, options.format.extension()))
+}
+
+/// Content of the 4 topics of an event's logs ('--topics-layout'): its `topic`, unless it's anonymous, then the types
+/// of its indexed parameters in order, empty for the unused topics. Parameters marked indexed beyond the EVM's
+/// 4 topics can't be, they're left out with a warning.
+fn topics_layout(topic: &str, indexed_types: &[String], event: &str) -> [String; 4] {
+    let topic0 = Some(topic.to_string()).filter(|topic| !topic.is_empty());
+    let slots: Vec<String> = topic0.into_iter().chain(indexed_types.iter().cloned()).collect();
+    if slots.len() > 4 {
+        warn!("'{}' has {} indexed parameters, more than its logs' topics can hold", event, indexed_types.len());
+    }
+    std::array::from_fn(|i| slots.get(i).cloned().unwrap_or_default())
+}
+
+/// Compute the selectors, topics and errors of a contract from its ABI.
+fn contract_data(contract_name: &str, abi_entries: Vec<AbiEntry>, options: &Options) -> ContractData {
+    debug!("{} entries in the ABI of '{}'", abi_entries.len(), contract_name);
+    if abi_entries.is_empty() {
+        info!("Empty ABI for '{}', it's left out of the tables", contract_name);
+    }
+    let mut contract = ContractData {
+        name: contract_name.to_string(),
+        functions: Vec::new(),
+        events: Vec::new(),
+        errors: Vec::new(),
+        constructors: Vec::new(),
+        interface: options
+            .emit_interface
+            .then(|| interface::solidity_interface(contract_name, &abi_entries, options.group_by_mutability)),
+        struct_types: if options.typehash { eip712_types(&abi_entries) } else { Vec::new() },
+    };
+
+    // Parsing each ABI's entry
+    for (index, entry) in abi_entries.into_iter().enumerate() {
+        let index = options.with_index.then_some(index);
+        // Hand-written ABIs may keep the Solidity types, e.g. 'address payable'
+        for (written, resolved) in resolved_types(&entry) {
+            info!(
+                "Type '{}' in '{}' of contract '{}' read as '{}'",
+                written,
+                entry.name.as_deref().unwrap_or(&entry.kind),
+                contract_name,
+                resolved
+            );
+        }
+        // A bogus type is still hashed, but flagged so it gets noticed
+        for invalid in invalid_types(&entry) {
+            warn!(
+                "Invalid ABI type '{}' in '{}' of contract '{}', its hash can't match a real one",
+                invalid,
+                signature_from_abi(&entry),
+                contract_name
+            );
+        }
+        match entry.kind.as_str() {
+            "function" if options.wants("selectors") => {
+                let signature = signature_from_abi(&entry);
+                // Selector: take keccak256 hash & extract 4 1st bytes (unless '--hash' or '--selector-bytes')
+                let selector_hex = format!("0x{}", hex::encode(options.hasher.selector(&signature)));
+                let hash = options.full_hash.then(|| format!("0x{}", hex::encode(options.hasher.hash(&signature))));
+
+                contract.functions.push(FunctionRow {
+                    contract: contract_name.to_string(),
+                    signature,
+                    selector: selector_hex,
+                    state_mutability: entry.state_mutability.clone(),
+                    hash,
+                    known_signatures: None,
+                    known_as: None,
+                    names: options.with_names.then(|| parameter_names(&entry)),
+                    index,
+                    source: None,
+                    repo: None,
+                    inputs: match options.manifest {
+                        Some(_) => parameters(&entry, options.with_names),
+                        None => Vec::new(),
+                    },
+                    payable: entry.state_mutability.as_deref() == Some("payable"),
+                });
+            },
+            "event" if options.wants("events") => {
+                let signature = signature_from_abi(&entry);
+                // Positions of the indexed parameters
+                let indexed = entry
+                    .inputs
                     .iter()
-                    .map(|c| process_type_with_components(c))
-                    .collect();
-                format!("({})", component_types.join(","))
-            } else {
-                "tuple".to_string()
+                    .flatten()
+                    .enumerate()
+                    .filter(|(_, inp)| inp.indexed)
+                    .map(|(i, _)| i)
+                    .collect::<Vec<_>>();
+                // Topic: take full keccak256 hash, never emitted by anonymous events
+                let topic_hex = if entry.anonymous {
+                    String::new()
+                } else {
+                    format!("0x{}", hex::encode(options.hasher.topic(&signature)))
+                };
+                let (indexed_types, data_types) = event_layout(&entry);
+                let topics = options.topics_layout.then(|| {
+                    topics_layout(&topic_hex, &indexed_types, &format!("{}.{}", contract_name, signature))
+                });
+
+                contract.events.push(EventRow {
+                    contract: contract_name.to_string(),
+                    signature,
+                    topic: topic_hex,
+                    indexed,
+                    anonymous: entry.anonymous,
+                    indexed_types,
+                    data_types,
+                    topics_layout: topics,
+                    names: options.with_names.then(|| parameter_names(&entry)),
+                    index,
+                    source: None,
+                    repo: None,
+                });
+            },
+            "error" if options.wants("errors") => {
+                let signature = signature_from_abi(&entry);
+                // Selector: same as functions, 4 1st bytes of keccak256 hash
+                let selector_hex = format!("0x{}", hex::encode(options.hasher.selector(&signature)));
+
+                contract.errors.push(ErrorRow {
+                    contract: contract_name.to_string(),
+                    signature,
+                    selector: selector_hex,
+                    names: options.with_names.then(|| parameter_names(&entry)),
+                    index,
+                    source: None,
+                    repo: None,
+                });
+            },
+            "receive" | "fallback" if options.wants("selectors") => {
+                // Part of the dispatch, but without selector
+                contract.functions.push(FunctionRow {
+                    contract: contract_name.to_string(),
+                    signature: format!("{}()", entry.kind),
+                    selector: String::new(),
+                    state_mutability: entry.state_mutability.clone(),
+                    hash: None,
+                    known_signatures: None,
+                    known_as: None,
+                    names: options.with_names.then(String::new),
+                    index,
+                    source: None,
+                    repo: None,
+                    inputs: Vec::new(),
+                    payable: entry.state_mutability.as_deref() == Some("payable"),
+                });
+            },
+            "constructor" if options.wants("constructors") => {
+                // No selector, only the arguments' types are useful (e.g. to encode deployment data)
+                contract.constructors.push(ConstructorRow {
+                    contract: contract_name.to_string(),
+                    signature: signature_from_abi(&entry),
+                    source: None,
+                    repo: None,
+                });
+            },
+            _ => {} // Other types (and kinds left out with '--only') ignored
+        }
+    }
+
+    contract.dedup();
+    contract
+}
+
+/// Artifacts' folder of a .sol file: `out/<File>.sol` in its forge project's `root`. When several files have the
+/// same name, forge writes the others' artifacts under their parent folders, e.g. `out/mock/Token.sol`, so the
+/// longest existing one is taken.
+fn artifacts_dir(path: &Path, root: &Path) -> PathBuf {
+    let out = root.join(ARTIFACTS_DIR);
+    let components: Vec<&OsStr> = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect();
+    (0..components.len())
+        .map(|i| out.join(components[i..].iter().collect::<PathBuf>()))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| out.join(path.file_name().unwrap_or_default()))
+}
+
+/// Names of the contracts defined in a .sol file, taken from its artifacts (one `<Contract>.json` each) in its forge
+/// project's `root`, none with '--solc'. If there are no artifacts, they're the contracts declared in the source,
+/// or the file's name if none is found.
+fn contract_names(path: &Path, root: Option<&Path>) -> anyhow::Result<Vec<String>> {
+    let mut names: Vec<String> = root
+        .and_then(|root| std::fs::read_dir(artifacts_dir(path, root)).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|ext| ext == "json").unwrap_or(false))
+        .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string()))
+        .collect();
+    if names.is_empty() && !is_vyper(path) {
+        let source = std::fs::read_to_string(path).with_context(|| format!("File {:?} couldn't be read", path))?;
+        names = declared_contracts(&source);
+    }
+    if names.is_empty() {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .with_context(|| format!("Contract's name couldn't be extracted from {:?}", path))?;
+        names.push(stem.to_string());
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Command running `program` in `dir`, or in the current folder if it's empty.
+fn command_in(program: &str, dir: &Path) -> Command {
+    let mut command = Command::new(program);
+    if !dir.as_os_str().is_empty() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// Run `program` with `args` in `dir` for a contract file, returning the bytes of its standard output, parsed as
+/// they are (a huge ABI isn't copied into a `String` first). `label` names the command in errors.
+/// Spawning may fail when too many processes or files are open, it's retried a few times before giving up.
+fn run_tool(program: &str, args: &[&str], dir: &Path, label: &str, file_path: &str) -> anyhow::Result<Vec<u8>> {
+    let mut command = command_in(program, dir);
+    command.args(args);
+    let mut attempt = 1;
+    let output = loop {
+        match command.output() {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound && attempt < SPAWN_ATTEMPTS => {
+                debug!("Couldn't run '{}' for {} (attempt {}): {}", label, file_path, attempt, err);
+                std::thread::sleep(Duration::from_millis(200 * attempt as u64));
+                attempt += 1;
             }
+            result => break result.map_err(|err| spawn_error(program, label, err))?,
         }
-        _ => input.param_type.clone()
+    };
+
+    if !output.status.success() {
+        // The whole command, to run it again by hand
+        let command_line = format!("{} {}", program, args.join(" "));
+        let location = if dir.as_os_str().is_empty() { String::new() } else { format!(" in {:?}", dir) };
+        let status = match output.status.code() {
+            Some(code) => format!("exit code {}", code),
+            None => "killed by a signal".to_string(),
+        };
+        bail!(
+            "'{}'{} failed for {} ({}): {}",
+            command_line,
+            location,
+            file_path,
+            status,
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Error of a command that couldn't be started, telling what to do when its program isn't installed.
+fn spawn_error(program: &str, label: &str, err: std::io::Error) -> anyhow::Error {
+    if err.kind() != std::io::ErrorKind::NotFound {
+        return anyhow::Error::new(err).context(format!("Error running '{}'", label));
     }
+    let hint = match label {
+        "forge compile" => "install Foundry or use --abi/--no-compile",
+        "forge inspect" => "install Foundry or use --abi, or the artifacts without --use-inspect/--from-method-ids",
+        "cast sig" => "install Foundry or drop --verify",
+        "vyper" => "install Vyper or drop --include-vyper",
+        "solc" => "install the Solidity compiler or drop --solc",
+        "git diff" => "install git or drop --since",
+        _ => "install it",
+    };
+    anyhow!("{} not found on PATH; {}", program, hint)
+}
+
+/// Run 'forge inspect' on a .sol file's contract to get one of its fields (e.g. 'abi'), in its forge project's `root`.
+fn forge_inspect(path: &Path, root: &Path, contract_name: &str, field: &str) -> anyhow::Result<Vec<u8>> {
+    let file_path = path.to_str().with_context(|| format!("Invalid path {:?}", path))?;
+    let project_path = path.strip_prefix(root).unwrap_or(path).to_str().unwrap_or(file_path);
+    debug!("Running 'forge inspect {}:{} {}' in {:?}", project_path, contract_name, field, root);
+
+    // Running: 'forge inspect <contract_file.sol>:<ContractName> <field>'
+    let contract = format!("{}:{}", project_path, contract_name);
+    run_tool("forge", &["inspect", &contract, field], root, "forge inspect", file_path)
+}
+
+/// Run the Vyper compiler on a .vy file to get one of its outputs (e.g. 'abi').
+fn vyper_output(path: &Path, format: &str) -> anyhow::Result<Vec<u8>> {
+    let file_path = path.to_str().with_context(|| format!("Invalid path {:?}", path))?;
+    debug!("Running 'vyper -f {} {}'", format, file_path);
+    run_tool("vyper", &["-f", format, file_path], Path::new(""), "vyper", file_path)
+}
+
+/// A Vyper source file, only discovered with '--include-vyper'.
+fn is_vyper(path: &Path) -> bool {
+    path.extension().map(|ext| ext == "vy").unwrap_or(false)
+}
+
+/// Run 'forge inspect' on a .sol file's contract to get its ABI.
+fn inspect_abi(path: &Path, root: &Path, contract_name: &str) -> anyhow::Result<Vec<AbiEntry>> {
+    info!("Checking contract '{}' from file '{}'", contract_name, path.display());
+    let abi_json = forge_inspect(path, root, contract_name, "abi")?;
+    let abi_entries: Vec<AbiEntry> = serde_json::from_slice(&abi_json)
+        .with_context(|| format!("Error parsing ABI's JSON of {}", path.display()))?;
+
+    Ok(abi_entries)
+}
+
+/// Run 'vyper -f abi' on a .vy file, which defines a single contract.
+fn vyper_abi(path: &Path, contract_name: &str) -> anyhow::Result<Vec<AbiEntry>> {
+    info!("Checking contract '{}' from file '{}'", contract_name, path.display());
+    let abi_json = vyper_output(path, "abi")?;
+    serde_json::from_slice(&abi_json).with_context(|| format!("Error parsing ABI's JSON of {}", path.display()))
 }
 
-/// Special character cases for .csv: comas, quotes or line breaks, enclose between quotes.
-fn escape_csv_field(field: &str) -> String {
-    if field.contains(',') || field.contains('"') || field.contains('\n') {
-        let escaped = field.replace('"', "\"\"");
-        format!("\"{}\"", escaped)
+/// Function selectors computed by the compiler ('forge inspect ... methodIdentifiers',
+/// or 'vyper -f method_identifiers' for .vy files), by signature.
+fn inspect_method_ids(path: &Path, root: &Path, contract_name: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let ids_json = if is_vyper(path) {
+        vyper_output(path, "method_identifiers")?
     } else {
-        field.to_string()
+        forge_inspect(path, root, contract_name, "methodIdentifiers")?
+    };
+    serde_json::from_slice(&ids_json)
+        .with_context(|| format!("Error parsing methodIdentifiers' JSON of {}", path.display()))
+}
+
+/// Names and canonical types of an ABI entry's parameters, with the fields of the structs if `with_fields`.
+fn parameters(entry: &AbiEntry, with_fields: bool) -> Vec<Parameter> {
+    let inputs = entry.inputs.iter().flatten();
+    inputs
+        .map(|input| {
+            let fields = if with_fields { component_names(input) } else { Vec::new() };
+            let fields = fields.into_iter().map(|(name, param_type)| Parameter { name, param_type, fields: Vec::new() });
+            Parameter {
+                name: input.name.clone().unwrap_or_default(),
+                param_type: canonical_type(input),
+                fields: fields.collect(),
+            }
+        })
+        .collect()
+}
+
+/// Replace the functions computed from the ABI by the compiler's method identifiers.
+/// The state mutability, the parameters' names and types are kept from the ABI's entry with the same signature,
+/// and `receive()`/`fallback()` are kept as they are.
+fn apply_method_ids(contract: &mut ContractData, method_ids: BTreeMap<String, String>, options: &Options) {
+    let (mut from_abi, without_selector): (Vec<FunctionRow>, Vec<FunctionRow>) =
+        std::mem::take(&mut contract.functions).into_iter().partition(|f| !f.selector.is_empty());
+    contract.functions = without_selector;
+    for (signature, selector) in method_ids {
+        let mut from_abi = from_abi.iter_mut().find(|f| f.signature == signature);
+        let state_mutability = from_abi.as_ref().and_then(|f| f.state_mutability.clone());
+        let payable = from_abi.as_ref().is_some_and(|f| f.payable);
+        let index = from_abi.as_ref().and_then(|f| f.index);
+        let inputs = from_abi.as_mut().map(|f| std::mem::take(&mut f.inputs)).unwrap_or_default();
+        let names = from_abi.and_then(|f| f.names.take()).or_else(|| options.with_names.then(String::new));
+        let hash = options.full_hash.then(|| format!("0x{}", hex::encode(options.hasher.hash(&signature))));
+        contract.functions.push(FunctionRow {
+            contract: contract.name.clone(),
+            signature,
+            selector: format!("0x{}", selector.trim_start_matches("0x")),
+            state_mutability,
+            hash,
+            known_signatures: None,
+            known_as: None,
+            names,
+            index,
+            source: None,
+            repo: None,
+            inputs,
+            payable,
+        });
     }
 }
 
-fn main() {
-    // Taking args from terminal commands:
-    // 1st arg: contract's folder path.
-    // 2nd arg (optional): output's folder path.
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("How to use: {} <contracts_folder_oath> [output_folder_path]", args[0]);
-        std::process::exit(1);
+/// Run 'solc --combined-json abi' on a .sol file to get a contract's ABI ('--solc'), without a forge project.
+fn solc_abi(path: &Path, contract_name: &str) -> anyhow::Result<Vec<AbiEntry>> {
+    let file_path = path.to_str().with_context(|| format!("Invalid path {:?}", path))?;
+    info!("Checking contract '{}' from file '{}' with solc", contract_name, path.display());
+    let output = run_tool("solc", &["--combined-json", "abi", file_path], Path::new(""), "solc", file_path)?;
+    let solc_output: SolcOutput = serde_json::from_slice(&output)
+        .with_context(|| format!("Error parsing solc's JSON of {}", path.display()))?;
+
+    // The file's own contract, or another file's one with that name (solc may rewrite the path)
+    let (mut own, mut other) = (None, None);
+    for (key, contract) in solc_output.contracts {
+        match key.rsplit_once(':') {
+            Some((file, name)) if name == contract_name && Path::new(file) == path => own = Some(contract),
+            Some((_, name)) if name == contract_name => {
+                other.get_or_insert(contract);
+            }
+            _ => {}
+        }
     }
-    let contracts_path = Path::new(&args[1]);
-    let output_dir_arg = if args.len() >= 3 { &args[2] } else { "function_selectors" };
-    let output_dir = Path::new(output_dir_arg);
+    let Some(contract) = own.or(other) else {
+        bail!("solc compiled no contract '{}' for {}", contract_name, path.display());
+    };
+    let abi_entries = match contract.abi {
+        serde_json::Value::String(abi_json) => serde_json::from_str(&abi_json),
+        abi => serde_json::from_value(abi),
+    };
+    abi_entries.with_context(|| format!("Error parsing ABI's JSON of {}", path.display()))
+}
+
+/// Check that solc can be run ('--solc'), before running it on each contract.
+fn check_solc() -> anyhow::Result<Option<String>> {
+    let output = Command::new("solc").arg("--version").output().map_err(|err| spawn_error("solc", "solc", err))?;
+    let version = String::from_utf8_lossy(&output.stdout);
+    debug!("Using {}", version.lines().last().unwrap_or_default());
+    // 'Version: 0.8.24+commit.e11b9ed9.Linux.g++', without the platform
+    let version = version.lines().find_map(|line| line.strip_prefix("Version: "));
+    Ok(version.map(|version| match version.split_once('+') {
+        Some((number, build)) => format!("{}+{}", number, build.split('.').next().unwrap_or_default()),
+        None => version.trim().to_string(),
+    }))
+}
+
+/// Read the ABI of a .sol file's contract from the artifacts written by 'forge compile'.
+fn artifact_abi(path: &Path, root: &Path, contract_name: &str) -> anyhow::Result<Vec<AbiEntry>> {
+    let artifact_path = artifacts_dir(path, root).join(format!("{}.json", contract_name));
+
+    info!("Checking contract '{}' from artifact {:?}", contract_name, artifact_path);
+
+    let artifact_json = std::fs::read_to_string(&artifact_path).with_context(|| {
+        format!("Artifact {:?} couldn't be read for {:?} (try --use-inspect)", artifact_path, path)
+    })?;
+    let artifact: Artifact = serde_json::from_str(&artifact_json)
+        .with_context(|| format!("Error parsing artifact's JSON {:?}", artifact_path))?;
 
-    // Compile contracts using 'forge compile' <-- this is required to generate the ABI
-    // WARNING: this script assumes that 'forge' is installed in the current project.
-    println!("Compiling contracts with 'forge compile'...");
-    let compile_output = Command::new("forge")
+    Ok(artifact.abi)
+}
+
+/// Compile contracts using 'forge compile' <-- this is required to generate the ABI (artifacts under 'out')
+/// WARNING: this script assumes that 'forge' is installed in the forge project's `root` (the current one if empty).
+fn compile_contracts(root: &Path, allow_errors: bool) -> anyhow::Result<Vec<String>> {
+    if root.as_os_str().is_empty() {
+        info!("Compiling contracts with 'forge compile'...");
+    } else {
+        info!("Compiling contracts of {:?} with 'forge compile'...", root);
+    }
+    let compile_output = command_in("forge", root)
         .arg("compile")
         .output()
-        .expect("Error running 'forge compile'");
+        .map_err(|err| spawn_error("forge", "forge compile", err))?;
 
+    let stdout = String::from_utf8_lossy(&compile_output.stdout);
+    let stderr = String::from_utf8_lossy(&compile_output.stderr);
+    let diagnostics = compile_diagnostics(&format!("{}\n{}", stdout, stderr));
+    for warning in diagnostics.iter().filter(|d| !d.is_error) {
+        match &warning.location {
+            Some(location) => warn!("{} ({})", warning.message, location),
+            None => warn!("{}", warning.message),
+        }
+    }
     if !compile_output.status.success() {
-        eprintln!(
-            "Compilation failed: {}",
-            String::from_utf8_lossy(&compile_output.stderr)
+        if !allow_errors {
+            bail!("Compilation failed: {}", stderr);
+        }
+        for compile_error in diagnostics.iter().filter(|d| d.is_error) {
+            match &compile_error.location {
+                Some(location) => error!("{} ({})", compile_error.message, location),
+                None => error!("{}", compile_error.message),
+            }
+        }
+        warn!("Compilation failed, going on with the artifacts that were built (--allow-compile-errors)");
+    } else {
+        info!("Contracts successfully compiled.");
+    }
+    Ok(compiler_versions(&stdout))
+}
+
+/// Solc version pinned in the project's config ('forge config'), if any: it's auto-detected otherwise.
+fn forge_config_solc(root: &Path) -> Option<String> {
+    let output = command_in("forge", root).args(["config", "--json"]).output().ok()?;
+    if !output.status.success() {
+        debug!("'forge config' failed: {}", String::from_utf8_lossy(&output.stderr));
+        return None;
+    }
+    let config: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let solc = config.get("solc").or_else(|| config.get("solc_version"))?.as_str()?;
+    // It can be the path of a solc binary too
+    solc.starts_with(|c: char| c.is_ascii_digit()).then(|| solc.to_string())
+}
+
+/// Selector given by 'cast sig' for a signature.
+fn cast_selector(signature: &str) -> anyhow::Result<String> {
+    let output = Command::new("cast")
+        .args(["sig", signature])
+        .output()
+        .map_err(|err| spawn_error("cast", "cast sig", err))?;
+    if !output.status.success() {
+        bail!("'cast sig {}' failed: {}", signature, String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_lowercase())
+}
+
+/// Check every function's selector against Foundry's, logging the mismatches and returning their number.
+fn verify_selectors(contracts: &[ContractData]) -> anyhow::Result<usize> {
+    // Each signature is only checked once, even if several contracts define it.
+    let functions: BTreeMap<&str, &str> = contracts
+        .iter()
+        .flat_map(|c| &c.functions)
+        .filter(|f| !f.selector.is_empty())
+        .map(|f| (f.signature.as_str(), f.selector.as_str()))
+        .collect();
+    info!("Verifying {} selectors with 'cast sig'...", functions.len());
+    let expected = functions
+        .par_iter()
+        .map(|(signature, _)| cast_selector(signature))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut mismatches = 0;
+    for ((signature, selector), expected) in functions.iter().zip(expected) {
+        if *selector != expected {
+            error!("Selector mismatch for '{}': computed {}, 'cast sig' gives {}", signature, selector, expected);
+            mismatches += 1;
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Functions of the proxy and of its implementation sharing a selector ('--proxy' and '--impl'), as
+/// `[selector, proxySignature, implementationSignature]` CSV rows. Calls to such a selector never reach
+/// the implementation, it's logged as an error.
+fn proxy_clashes(contracts: &[ContractData], proxy: &str, implementation: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let find = |name: &str| {
+        contracts.iter().find(|c| c.name == name).with_context(|| format!("No contract named '{}'", name))
+    };
+    let (proxy, implementation) = (find(proxy)?, find(implementation)?);
+    let mut rows = vec![vec!["selector".to_string(), "proxySignature".to_string(), "implementationSignature".to_string()]];
+    for proxy_function in proxy.functions.iter().filter(|f| !f.selector.is_empty()) {
+        for impl_function in implementation.functions.iter().filter(|f| f.selector == proxy_function.selector) {
+            error!(
+                "Proxy selector clash {}: '{}' in '{}' shadows '{}' in '{}'",
+                proxy_function.selector, proxy_function.signature, proxy.name, impl_function.signature, implementation.name
+            );
+            rows.push(vec![
+                proxy_function.selector.clone(),
+                proxy_function.signature.clone(),
+                impl_function.signature.clone(),
+            ]);
+        }
+    }
+    Ok(rows)
+}
+
+/// Notable selector a function has ('--flag-known'), e.g. `ERC-165 supportsInterface(bytes4)`.
+/// Another function with that selector is a clash, it's warned about.
+fn known_annotation(function: &FunctionRow) -> Option<String> {
+    let selector = hex::decode(function.selector.trim_start_matches("0x")).ok()?;
+    let known = known_selector(selector.try_into().ok()?)?;
+    let annotation = if known.signature.is_empty() {
+        known.label.to_string()
+    } else {
+        format!("{} {}", known.label, known.signature)
+    };
+    // Without signature (e.g. '--from-bytecode'), it can't be told
+    if !function.signature.is_empty() && function.signature != known.signature {
+        warn!(
+            "'{}' of '{}' has the selector {} ({})",
+            function.signature, function.contract, function.selector, annotation
         );
-        std::process::exit(1);
     }
-    println!("Contracts successfully compiled.");
+    Some(annotation)
+}
 
-    // Create sub-folders: selectors & events
-    let selectors_dir = output_dir.join("selectors");
-    let events_dir = output_dir.join("events");
-    create_dir_all(&selectors_dir).expect("'selectors' folder couldn't be created");
-    create_dir_all(&events_dir).expect("'events' folder couldn't be created");
+/// Compute the data of a .sol (or .vy) file's contract from its ABI (read if not cached).
+/// The ABI is returned along to be cached, with '--cache'.
+fn process_contract(
+    path: &Path,
+    name: &str,
+    cached_abi: Option<&Vec<AbiEntry>>,
+    overrides: &BTreeMap<String, Vec<AbiEntry>>,
+    options: &Options,
+) -> anyhow::Result<(ContractData, Option<Vec<AbiEntry>>)> {
+    let override_abi = overrides.get(name);
+    let abi = match (override_abi, cached_abi) {
+        (Some(abi), _) => {
+            info!("Using the ABI override of '{}'", name);
+            abi.clone()
+        }
+        (None, Some(abi)) => {
+            debug!("Using the cached ABI of '{}'", name);
+            abi.clone()
+        }
+        _ if is_vyper(path) => vyper_abi(path, name)?,
+        _ if options.solc => solc_abi(path, name)?,
+        _ if options.use_inspect => inspect_abi(path, project_root(path, options), name)?,
+        _ => artifact_abi(path, project_root(path, options), name)?,
+    };
+    let abi_to_cache = options.cache.then(|| abi.clone());
+    let mut contract = contract_data(name, abi, options);
+    // The override is what the contract is, whatever forge says
+    if options.from_method_ids && options.wants("selectors") && override_abi.is_none() {
+        apply_method_ids(&mut contract, inspect_method_ids(path, project_root(path, options), name)?, options);
+    }
+    // Contracts' folder the file was found in, none with '--files-from' or '--contract'
+    let folder = options.contracts_paths.iter().map(Path::new).find(|folder| path.starts_with(folder));
+    if options.with_source {
+        // Relative to the contracts' folder, with '/' separators on every platform
+        let relative = folder.and_then(|folder| path.strip_prefix(folder).ok()).unwrap_or(path);
+        contract.set_source(&relative.iter().map(|c| c.to_string_lossy()).collect::<Vec<_>>().join("/"));
+    }
+    if let (true, Some(folder)) = (options.merges_folders(), folder) {
+        contract.set_repo(&folder.to_string_lossy());
+    }
+    Ok((contract, abi_to_cache))
+}
 
-    // Definitions for csv rows
-    // Array for each row: [contractName, <signature>, <selector or topic>]
-    let mut csv_events: Vec<[String; 3]> = Vec::new();
-    let mut csv_selectors: Vec<[String; 3]> = Vec::new();
-    // Adding headers
-    csv_events.push(["contractName".to_string(), "event".to_string(), "topic".to_string()]);
-    csv_selectors.push(["contractName".to_string(), "function".to_string(), "selector".to_string()]);
+/// A .sol file, or a .vy file with '--include-vyper'.
+fn is_contract_file(path: &Path, options: &Options) -> bool {
+    let extension = path.extension();
+    extension.map(|ext| ext == "sol" || (options.include_vyper && ext == "vy")).unwrap_or(false)
+}
 
-    // Recursively check the contract's folder looking for .sol files
-    for entry in WalkDir::new(contracts_path)
+/// Recursively check the contract's folder looking for .sol files (and .vy files with '--include-vyper'),
+/// down to '--max-depth' and skipping the vendored folders unless '--no-default-excludes'.
+fn find_contract_files(contracts_path: &Path, filter: &PathFilter, options: &Options) -> anyhow::Result<Vec<PathBuf>> {
+    // The generated files (e.g. the interfaces' .sol files) mustn't be read back as contracts
+    let output_dir = nested_output_dir(contracts_path, options);
+    if output_dir.as_ref().is_some_and(|dir| dir.as_os_str().is_empty()) {
+        bail!(
+            "The output folder {:?} is the contracts' folder, the generated files would be read back as contracts: \
+             give another output folder, e.g. outside of {:?}",
+            options.output_dir,
+            contracts_path
+        );
+    }
+    if let Some(dir) = &output_dir {
+        debug!("Skipping the output folder {:?} in {:?}", dir, contracts_path);
+    }
+    let mut walk = WalkDir::new(contracts_path);
+    if let Some(max_depth) = options.max_depth {
+        walk = walk.max_depth(max_depth);
+    }
+    let sol_files: Vec<PathBuf> = walk
         .into_iter()
+        .filter_entry(|e| {
+            // The contracts' folder itself is never skipped, even if it's e.g. 'lib'
+            let vendored = e.depth() > 0
+                && e.file_type().is_dir()
+                && e.file_name().to_str().is_some_and(|name| DEFAULT_EXCLUDES.contains(&name));
+            let output = output_dir.as_ref().is_some_and(|dir| e.path().strip_prefix(contracts_path).ok() == Some(dir));
+            (options.no_default_excludes || !vendored) && !output
+        })
         .filter_map(|e| e.ok())
+        .filter(|e| is_contract_file(e.path(), options))
+        .filter(|e| filter.matches(e.path()))
+        // forge is given the paths as strings, such a file could only be a failure of the run
         .filter(|e| {
-            e.path().extension().map(|ext| ext == "sol").unwrap_or(false)
+            let utf8 = e.path().to_str().is_some();
+            if !utf8 {
+                warn!("Skipping {:?}, its path isn't valid UTF-8", e.path());
+            }
+            utf8
         })
-    {
-        let file_path = entry.path().to_str().unwrap();
-        // Assuming that the contract's name matches the file's name.
-        let contract_name = entry
-            .path()
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .expect("Contract's name couldn't be extracted");
+        .map(|e| e.into_path())
+        .collect();
+    debug!("{} contract files found in {:?}", sol_files.len(), contracts_path);
+    Ok(sol_files)
+}
 
-        println!("Checking contract '{}' from file '{}'", contract_name, file_path);
+/// Contract files of every contracts' folder, those of nested folders being listed once.
+fn folders_contract_files(filter: &PathFilter, options: &Options) -> anyhow::Result<Vec<PathBuf>> {
+    let mut sol_files = Vec::new();
+    for folder in &options.contracts_paths {
+        let mut folder_files = find_contract_files(Path::new(folder), filter, options)?;
+        if let Some(git_ref) = &options.since {
+            match changed_files(Path::new(folder), git_ref)? {
+                Some(changed) => {
+                    folder_files.retain(|path| changed.contains(path));
+                    info!("{} contract file(s) of {:?} changed since '{}'", folder_files.len(), folder, git_ref);
+                }
+                None => warn!("{:?} isn't in a git repository, all its contract files are processed", folder),
+            }
+        }
+        sol_files.extend(folder_files);
+    }
+    let mut seen = BTreeSet::new();
+    sol_files.retain(|path| seen.insert(path.clone()));
+    Ok(sol_files)
+}
 
-        // Running: 'forge inspect <contract_file.sol>:<ContractName> abi'
-        let output = Command::new("forge")
-            .args(&["inspect", &format!("{}:{}", file_path, contract_name), "abi"])
-            .output()
-            .expect("Error running 'forge inspect'");
+/// Files of a contracts' folder changed since `git_ref`, uncommitted changes included, from
+/// 'git diff --name-only'. `None` if the folder isn't in a git repository.
+fn changed_files(folder: &Path, git_ref: &str) -> anyhow::Result<Option<BTreeSet<PathBuf>>> {
+    let git = |args: &[&str]| {
+        let output = command_in("git", folder).args(args).output();
+        output.map_err(|err| spawn_error("git", "git diff", err))
+    };
+    if !git(&["rev-parse", "--is-inside-work-tree"])?.status.success() {
+        return Ok(None);
+    }
+    // Paths relative to the folder, NUL-separated so that git doesn't quote the unusual ones
+    let output = git(&["diff", "--name-only", "--relative", "-z", git_ref, "--"])?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("'git diff --name-only {}' failed in {:?}: {}", git_ref, folder, stderr.trim_end());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Some(stdout.split('\0').filter(|path| !path.is_empty()).map(|path| folder.join(path)).collect()))
+}
 
-        if !output.status.success() {
-            eprintln!(
-                "'forge inspect' command failed for {}: {}",
-                file_path,
-                String::from_utf8_lossy(&output.stderr)
-            );
+/// Closest folder with a `foundry.toml` from a contracts' folder (itself included), or the current folder if none.
+fn find_project_root(folder: &Path) -> PathBuf {
+    let root = folder.ancestors().find(|dir| dir.join("foundry.toml").is_file());
+    root.unwrap_or(Path::new("")).to_path_buf()
+}
+
+/// Forge project of a contract file, the one of the contracts' folder it was found in or the current folder
+/// (an empty path).
+fn project_root<'a>(path: &Path, options: &'a Options) -> &'a Path {
+    let mut folders = options.contracts_paths.iter().zip(&options.project_roots);
+    let root = folders.find(|(folder, _)| path.starts_with(folder)).map(|(_, root)| root.as_path());
+    root.unwrap_or(Path::new(""))
+}
+
+/// Path of the output folder relative to the contracts' folder, if it's inside it (empty if it's the same folder).
+/// Only an existing output folder is looked for, a new one has nothing to read back.
+fn nested_output_dir(contracts_path: &Path, options: &Options) -> Option<PathBuf> {
+    if options.stdout {
+        return None;
+    }
+    let output_dir = std::fs::canonicalize(&options.output_dir).ok()?;
+    let contracts_dir = std::fs::canonicalize(contracts_path).ok()?;
+    output_dir.strip_prefix(&contracts_dir).ok().map(Path::to_path_buf)
+}
+
+/// Read the newline-separated files to process ('--files-from'), from `list` or the standard input if it's '-'.
+/// Missing files are logged and skipped, like the ones that aren't contracts (e.g. from 'git diff --name-only').
+fn read_file_list(list: &str, filter: &PathFilter, options: &Options) -> anyhow::Result<Vec<PathBuf>> {
+    let content = if list == "-" {
+        std::io::read_to_string(std::io::stdin()).context("Error reading the files' list from the standard input")?
+    } else {
+        std::fs::read_to_string(list).with_context(|| format!("Files' list {:?} couldn't be read", list))?
+    };
+    let mut sol_files = Vec::new();
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let path = PathBuf::from(line);
+        if !is_contract_file(&path, options) || !filter.matches(&path) {
+            debug!("Skipping {:?}", path);
+        } else if !path.is_file() {
+            warn!("Skipping {:?}, the file doesn't exist", path);
+        } else if !sol_files.contains(&path) {
+            sol_files.push(path);
+        }
+    }
+    debug!("{} contract files listed", sol_files.len());
+    Ok(sol_files)
+}
+
+/// Contracts to process in a file: the ones given by '--contract', or all the contracts it defines.
+fn target_names(path: &Path, options: &Options) -> anyhow::Result<Vec<String>> {
+    if options.qualified_names.is_empty() {
+        return contract_names(path, (!options.solc).then(|| project_root(path, options)));
+    }
+    let selected = options.qualified_names.iter().filter(|(file, _)| file == path);
+    Ok(selected.map(|(_, name)| name.clone()).collect())
+}
+
+/// Files of the contracts given by '--contract', each one once.
+fn qualified_files(options: &Options) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for (path, name) in &options.qualified_names {
+        if !path.is_file() {
+            bail!("No file {:?} for the contract '{}'", path, name);
+        }
+        if !files.contains(path) {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Print the files that would be processed ('--dry-run'), each one with its contracts' names. They're taken from
+/// the existing artifacts or the sources, nothing is compiled. Only the counts are printed with '--quiet'.
+fn print_dry_run(sol_files: &[PathBuf], options: &Options) {
+    let mut contracts = 0;
+    for path in sol_files {
+        match target_names(path, options) {
+            Ok(names) => {
+                contracts += names.len();
+                if options.log_level >= LevelFilter::Warn {
+                    println!("{}: {}", path.display(), names.join(", "));
+                }
+            }
+            Err(err) => warn!("{:#}", err),
+        }
+    }
+    println!("{} contract file(s), {} contract(s) would be processed", sol_files.len(), contracts);
+}
+
+/// Compute the data of each contract defined in the given files.
+/// Contracts that fail are logged and skipped, the failures are returned along the data.
+fn scan_contracts(
+    sol_files: Vec<PathBuf>,
+    overrides: &BTreeMap<String, Vec<AbiEntry>>,
+    options: &Options,
+    progress: &MultiProgress,
+) -> (Vec<ContractData>, Vec<Failure>) {
+    let mut failed = Vec::new();
+    let mut cache = options.cache.then(Cache::load);
+    // Content hash of each file, only when caching
+    let hashes: Vec<Option<String>> = sol_files
+        .iter()
+        .map(|path| cache.as_ref().and_then(|_| cache::content_hash(path)))
+        .collect();
+
+    // A single file may define several contracts, each one gets its own entry.
+    // Unchanged files reuse the contracts and ABIs from the cache.
+    let mut targets: Vec<(&PathBuf, String, Option<&Vec<AbiEntry>>)> = Vec::new();
+    for (path, hash) in sol_files.iter().zip(&hashes) {
+        let cached = cache.as_ref().zip(hash.as_deref()).and_then(|(cache, hash)| cache.get(path, hash));
+        if let Some(contracts) = cached {
+            targets.extend(contracts.iter().map(|(name, abi)| (path, name.clone(), Some(abi))));
             continue;
         }
+        match target_names(path, options) {
+            Ok(names) => targets.extend(names.into_iter().map(|name| (path, name, None))),
+            Err(err) => failed.push(Failure::new(path, err)),
+        }
+    }
 
-        let abi_json = String::from_utf8_lossy(&output.stdout);
-        let abi_entries: Vec<AbiEntry> = serde_json::from_str(&abi_json)
-            .expect("Error parsing ABI's JSON");
-
-        // Definitions for contract's data (for CSV files & individual txt files)
-        let mut contract_events: Vec<(String, String)> = Vec::new();
-        let mut contract_functions: Vec<(String, String)> = Vec::new();
-
-        // Parsing each ABI's entry
-        for entry in abi_entries {
-            match entry.kind.as_str() {
-                "function" => {
-                    let name = entry.name.unwrap_or_else(|| "unknown".to_string());
-                    let input_types = entry
-                        .inputs
-                        .unwrap_or_default()
-                        .into_iter()
-                        .map(|inp| process_type_with_components(&inp))
-                        .collect::<Vec<_>>()
-                        .join(",");
-                    let signature = format!("{}({})", name, input_types);
-                    
-                    // Selector: take keccak256 hash & extract 4 1st bytes
-                    let hash = Keccak256::digest(signature.as_bytes());
-                    let selector = &hash[..4];
-                    let selector_hex = format!("0x{}", hex::encode(selector));
-                    
-                    contract_functions.push((signature, selector_hex));
-                },
-                "event" => {
-                    let name = entry.name.unwrap_or_else(|| "unknown".to_string());
-                    let input_types = entry
-                        .inputs
-                        .unwrap_or_default()
-                        .into_iter()
-                        .map(|inp| process_type_with_components(&inp))
-                        .collect::<Vec<_>>()
-                        .join(",");
-                    let signature = format!("{}({})", name, input_types);
-                    
-                    // Topic: take full keccak256 hash
-                    let hash = Keccak256::digest(signature.as_bytes());
-                    let topic_hex = format!("0x{}", hex::encode(hash));
-                    
-                    if entry.anonymous {
-                        contract_events.push((format!("{} [anonymous]", signature), topic_hex));
-                    } else {
-                        contract_events.push((signature, topic_hex));
-                    }
-                },
-                _ => {} // Other types ignored
-            }
-        }
-
-        // Write individual .txt files (optional)
-        // let selectors_output_file = selectors_dir.join(format!("{}.txt", contract_name));
-        // let events_output_file = events_dir.join(format!("{}.txt", contract_name));
-        // {
-        //     let mut file_func = File::create(&selectors_output_file)
-        //         .unwrap_or_else(|_| panic!("file {:?} couldn't be created", selectors_output_file));
-        //     for (sig, selector_hex) in &contract_functions {
-        //         writeln!(file_func, "{} -> {}", sig, selector_hex)
-        //             .unwrap_or_else(|_| panic!("Error writting in {:?}", selectors_output_file));
-        //     }
-        // }
-        // {
-        //     let mut file_event = File::create(&events_output_file)
-        //         .unwrap_or_else(|_| panic!("File {:?} couldn't be created", events_output_file));
-        //     for (sig, topic_hex) in &contract_events {
-        //         writeln!(file_event, "{} -> {}", sig, topic_hex)
-        //             .unwrap_or_else(|_| panic!("Error writting in {:?}", events_output_file));
-        //     }
-        // }
-        // println!("txt files successfully written for '{}'", contract_name);
-
-        // Add rows to the global CSV for each contract.
-        // 1st row: contract's name
-        csv_events.push([contract_name.to_string(), "".to_string(), "".to_string()]);
-        csv_selectors.push([contract_name.to_string(), "".to_string(), "".to_string()]);
-        // Then, a row for each event (leaving 1st column empty)
-        for (sig, topic_hex) in contract_events {
-            csv_events.push(["".to_string(), sig, topic_hex]);
-        }
-        // Then, a row for each function
-        for (sig, selector_hex) in contract_functions {
-            csv_selectors.push(["".to_string(), sig, selector_hex]);
-        }
-    }
-
-    // Finnaly, we write CSV files in their respective sub-folders
-    let events_csv_path = events_dir.join("events.csv");
-    let selectors_csv_path = selectors_dir.join("selectors.csv");
+    // Each contract is independent (artifact or 'forge inspect' subprocess), so they are processed in parallel.
+    let bar = progress.add(ProgressBar::new(targets.len() as u64));
+    bar.set_style(ProgressStyle::with_template("{bar:40} {pos}/{len} {msg}").expect("valid template"));
+    let results: Vec<anyhow::Result<(ContractData, Option<Vec<AbiEntry>>)>> = targets
+        .par_iter()
+        .map(|(path, name, cached_abi)| {
+            bar.set_message(name.clone());
+            let result = process_contract(path, name, *cached_abi, overrides, options);
+            bar.inc(1);
+            result
+        })
+        .collect();
+    bar.finish_and_clear();
 
-    {
-        let file = File::create(&events_csv_path).expect("events' CSV file couldn't be created");
-        let mut writer = BufWriter::new(file);
-        for row in csv_events {
-            let line = format!(
-                "{},{},{}\n",
-                escape_csv_field(&row[0]),
-                escape_csv_field(&row[1]),
-                escape_csv_field(&row[2])
-            );
-            writer.write_all(line.as_bytes()).expect("Error writting on event's CSV file");
+    // ABIs to cache for each file, none if one of its contracts failed
+    let mut to_cache: BTreeMap<&PathBuf, Option<FileAbis>> = BTreeMap::new();
+    let mut contracts = Vec::new();
+    let mut contract_paths = Vec::new();
+    for ((path, _, _), result) in targets.iter().zip(results) {
+        match result {
+            Ok((contract, abi)) => {
+                if let (Some(file_abis), Some(abi)) = (to_cache.entry(path).or_insert(Some(Vec::new())), abi) {
+                    file_abis.push((contract.name.clone(), abi));
+                }
+                contracts.push(contract);
+                contract_paths.push(path.as_path());
+            }
+            Err(err) => {
+                to_cache.insert(path, None);
+                failed.push(Failure::new(path, err));
+            }
         }
     }
-    {
-        let file = File::create(&selectors_csv_path).expect("selectors' CSV file couldn't be created");
-        let mut writer = BufWriter::new(file);
-        for row in csv_selectors {
-            let line = format!(
-                "{},{},{}\n",
-                escape_csv_field(&row[0]),
-                escape_csv_field(&row[1]),
-                escape_csv_field(&row[2])
+    // Cached with the names of the artifacts
+    if let Some(cache) = &mut cache {
+        for (path, hash) in sol_files.iter().zip(hashes) {
+            if let (Some(Some(file_abis)), Some(hash)) = (to_cache.remove(path), hash) {
+                cache.insert(path, hash, file_abis);
+            }
+        }
+        if let Err(err) = cache.save() {
+            warn!("{:#}", err);
+        }
+    }
+    disambiguate_names(&mut contracts, &contract_paths);
+    (contracts, failed)
+}
+
+/// Prefix the name of the contracts defined in several files by their folder, e.g. `core/Token` and `mock/Token`,
+/// instead of listing them under the same name. The folders common to all of them are left out, and the unique
+/// names are kept as they are. Files of the same folder are told apart by their name, e.g. `A.sol:Token`.
+fn disambiguate_names(contracts: &mut [ContractData], paths: &[&Path]) {
+    let mut by_name: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, contract) in contracts.iter().enumerate() {
+        by_name.entry(contract.name.clone()).or_default().push(i);
+    }
+    let with_slashes = |path: &Path| path.iter().map(|c| c.to_string_lossy()).collect::<Vec<_>>().join("/");
+    for (name, indexes) in by_name.into_iter().filter(|(_, indexes)| indexes.len() > 1) {
+        let dirs: Vec<&Path> = indexes.iter().map(|&i| paths[i].parent().unwrap_or(Path::new(""))).collect();
+        let mut common = dirs[0].to_path_buf();
+        while !dirs.iter().all(|dir| dir.starts_with(&common)) && common.pop() {}
+        let same_dir = dirs.iter().collect::<HashSet<_>>().len() < dirs.len();
+        for (&i, dir) in indexes.iter().zip(&dirs) {
+            let new_name = if same_dir {
+                format!("{}:{}", with_slashes(paths[i].strip_prefix(&common).unwrap_or(paths[i])), name)
+            } else {
+                match with_slashes(dir.strip_prefix(&common).unwrap_or(dir)) {
+                    relative if relative.is_empty() => continue,
+                    relative => format!("{}/{}", relative, name),
+                }
+            };
+            debug!("'{}' of {:?} renamed '{}', the name is used by several files", name, paths[i], new_name);
+            contracts[i].rename(new_name);
+        }
+    }
+}
+
+/// ABIs of the '--abi-override' files by contract's name, all of them checked before anything is compiled.
+fn read_abi_overrides(options: &Options) -> anyhow::Result<BTreeMap<String, Vec<AbiEntry>>> {
+    let mut overrides = BTreeMap::new();
+    for (name, path) in &options.abi_overrides {
+        let abi_json =
+            std::fs::read_to_string(path).with_context(|| format!("ABI override {:?} couldn't be read", path))?;
+        let abi: Vec<AbiEntry> = serde_json::from_str(&abi_json)
+            .with_context(|| format!("Error parsing the JSON of the ABI override {:?} of '{}'", path, name))?;
+        overrides.insert(name.clone(), abi);
+    }
+    Ok(overrides)
+}
+
+/// Read a raw ABI file (array of ABI entries, e.g. exported from Etherscan or Hardhat).
+/// The contract's name is `name` if given, the file's name otherwise.
+fn read_abi_file(path: &Path, name: Option<&str>) -> anyhow::Result<(String, Vec<AbiEntry>)> {
+    let contract_name = name
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()))
+        .with_context(|| format!("Contract's name couldn't be extracted from {:?}", path))?;
+
+    info!("Checking contract '{}' from ABI file {:?}", contract_name, path);
+
+    let abi_json = std::fs::read_to_string(path)
+        .with_context(|| format!("ABI file {:?} couldn't be read", path))?;
+    let abi_entries: Vec<AbiEntry> = serde_json::from_str(&abi_json)
+        .with_context(|| format!("Error parsing ABI's JSON {:?}", path))?;
+
+    Ok((contract_name.to_string(), abi_entries))
+}
+
+/// JSON files of an ABIs' folder ('--abi-dir'), down to '--max-depth' and sorted by path.
+fn find_abi_files(abi_dir: &Path, filter: &PathFilter, options: &Options) -> anyhow::Result<Vec<PathBuf>> {
+    if !abi_dir.is_dir() {
+        bail!("ABIs' folder {:?} couldn't be read", abi_dir);
+    }
+    let mut walk = WalkDir::new(abi_dir).sort_by_file_name();
+    if let Some(max_depth) = options.max_depth {
+        walk = walk.max_depth(max_depth);
+    }
+    let abi_files: Vec<PathBuf> = walk
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter(|e| filter.matches(e.path()))
+        .map(|e| e.into_path())
+        .collect();
+    debug!("{} JSON files found in {:?}", abi_files.len(), abi_dir);
+    Ok(abi_files)
+}
+
+/// Contracts of the ABI files of '--abi-dir', each one named after its file. The JSON files that aren't an ABI
+/// (e.g. a `package.json`) are skipped, the unreadable ones are failures.
+fn abi_dir_contracts(abi_files: &[PathBuf], options: &Options) -> (Vec<ContractData>, Vec<Failure>) {
+    let mut contracts = Vec::new();
+    let mut contract_paths = Vec::new();
+    let mut failed = Vec::new();
+    for path in abi_files {
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            warn!("Skipping {:?}, its name isn't valid UTF-8", path);
+            continue;
+        };
+        let abi_json = match std::fs::read_to_string(path) {
+            Ok(abi_json) => abi_json,
+            Err(err) => {
+                failed.push(Failure::new(path, anyhow!(err).context(format!("ABI file {:?} couldn't be read", path))));
+                continue;
+            }
+        };
+        match serde_json::from_str::<Vec<AbiEntry>>(&abi_json) {
+            Ok(abi) => {
+                info!("Checking contract '{}' from ABI file {:?}", name, path);
+                contracts.push(contract_data(name, abi, options));
+                contract_paths.push(path.as_path());
+            }
+            Err(err) => info!("Skipping {:?}, it isn't an ABI: {}", path, err),
+        }
+    }
+    disambiguate_names(&mut contracts, &contract_paths);
+    (contracts, failed)
+}
+
+/// Contract of a runtime bytecode file ('--from-bytecode'): a function without signature for each selector found in
+/// its dispatcher. The contract's name is taken from `--name` or the file's name.
+fn bytecode_contract(path: &Path, options: &Options) -> anyhow::Result<ContractData> {
+    let contract_name = options
+        .contract_name
+        .as_deref()
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()))
+        .with_context(|| format!("Contract's name couldn't be extracted from {:?}", path))?;
+
+    info!("Checking contract '{}' from bytecode file {:?}", contract_name, path);
+
+    let content = std::fs::read_to_string(path).with_context(|| format!("Bytecode file {:?} couldn't be read", path))?;
+    let digits: String = content.split_whitespace().collect();
+    let digits = digits.strip_prefix("0x").unwrap_or(&digits);
+    let bytecode = hex::decode(digits).with_context(|| format!("Invalid hex in bytecode file {:?}", path))?;
+    let selectors = dispatch_selectors(&bytecode);
+    if selectors.is_empty() {
+        warn!("No selector found in the dispatcher of {:?}, is it the runtime bytecode?", path);
+    }
+    debug!("{} selectors found in the bytecode of '{}'", selectors.len(), contract_name);
+
+    let functions = selectors
+        .into_iter()
+        .map(|selector| FunctionRow {
+            contract: contract_name.to_string(),
+            signature: String::new(),
+            selector: format!("0x{}", hex::encode(selector)),
+            state_mutability: None,
+            hash: None,
+            known_signatures: None,
+            known_as: None,
+            names: options.with_names.then(String::new),
+            index: None,
+            source: None,
+            repo: None,
+            inputs: Vec::new(),
+            payable: false,
+        })
+        .collect();
+    Ok(ContractData {
+        name: contract_name.to_string(),
+        functions,
+        events: Vec::new(),
+        errors: Vec::new(),
+        constructors: Vec::new(),
+        interface: None,
+        struct_types: Vec::new(),
+    })
+}
+
+/// 'sig' subcommand: print the selector of each signature given, and its topic with '--event'.
+fn print_signatures(args: &[String]) -> Result<(), String> {
+    let event = args.iter().any(|arg| arg == "--event");
+    let signatures: Vec<&String> = args.iter().filter(|arg| arg.as_str() != "--event").collect();
+    if let Some(flag) = signatures.iter().find(|arg| arg.starts_with('-')) {
+        return Err(format!("Unknown option '{}' for 'sig'", flag));
+    }
+    if signatures.is_empty() {
+        return Err("'sig' requires at least one signature".to_string());
+    }
+    // Checking all of them before printing anything
+    let canonical = signatures.iter().map(|s| canonical_signature(s)).collect::<Result<Vec<_>, _>>();
+    let canonical = canonical.map_err(|err| err.to_string())?;
+    for signature in canonical {
+        let selector = format!("0x{}", hex::encode(function_selector(&signature)));
+        if event {
+            println!("{} {} 0x{}", signature, selector, hex::encode(event_topic(&signature)));
+        } else {
+            println!("{} {}", signature, selector);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    // Taking args from terminal commands:
+    // 1st arg: contract's folder path (or '--abi <file>' to read a raw ABI without forge).
+    // 2nd arg (optional): output's folder path.
+    // Then the options listed in USAGE.
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("sig") {
+        if let Err(err) = print_signatures(&args[2..]) {
+            eprintln!("{}", err);
+            eprintln!("How to use: {} {}", args[0], USAGE);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("How to use: {} {}", args[0], USAGE);
+            std::process::exit(1);
+        }
+    };
+    // Dependencies (e.g. the HTTP client) only log their warnings, even when verbose.
+    let logger = env_logger::Builder::new()
+        .filter_level(options.log_level.min(LevelFilter::Warn))
+        .filter_module("selector_calculator", options.log_level)
+        .format_timestamp(None)
+        .format_target(false)
+        .build();
+    // Log lines are printed above the progress bar instead of breaking it. No bar when quiet.
+    let progress = if options.log_level < LevelFilter::Warn {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    } else {
+        MultiProgress::new()
+    };
+    let max_level = logger.filter();
+    LogWrapper::new(progress.clone(), logger).try_init().context("Logger couldn't be initialized")?;
+    log::set_max_level(max_level);
+    if let Some(jobs) = options.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .context("Thread pool couldn't be created")?;
+    }
+    let filter = PathFilter::new(&options.include, &options.exclude).unwrap_or_else(|err| {
+        error!("Invalid glob: {}", err);
+        std::process::exit(1);
+    });
+
+    if options.watch {
+        let contracts_path = Path::new(&options.contracts_paths[0]);
+        return watch::watch(
+            contracts_path,
+            |path| {
+                // Nor the files generated in a nested output folder, e.g. the interfaces
+                let generated = nested_output_dir(contracts_path, &options).is_some_and(|dir| {
+                    path.strip_prefix(contracts_path).is_ok_and(|relative| relative.starts_with(&dir))
+                });
+                is_contract_file(path, &options) && filter.matches(path) && !generated
+            },
+            || run(&options, &filter, &progress),
+        );
+    }
+    run(&options, &filter, &progress).map(|_| ())
+}
+
+/// Compute and write everything asked by `options`, returning the number of functions' and errors' selectors.
+fn run(options: &Options, filter: &PathFilter, progress: &MultiProgress) -> anyhow::Result<usize> {
+    // Hashes of the previous runs aren't kept, on rayon's threads and this one
+    clear_hash_cache();
+    rayon::broadcast(|_| clear_hash_cache());
+    let output_dir = Path::new(&options.output_dir);
+    let single_contract = (&options.abi_path, &options.address, &options.from_bytecode);
+    // Number of contract files found, none with a single contract
+    let mut contract_files = None;
+    // Solc versions used to compile, unknown with a single contract
+    let mut solc_versions: BTreeSet<String> = BTreeSet::new();
+    let (mut contracts, failed) = match single_contract {
+        (Some(abi_path), _, _) => {
+            let (name, abi) = read_abi_file(Path::new(abi_path), options.contract_name.as_deref())?;
+            (vec![contract_data(&name, abi, options)], Vec::new())
+        }
+        (None, None, Some(bytecode_path)) => {
+            (vec![bytecode_contract(Path::new(bytecode_path), options)?], Vec::new())
+        }
+        (None, Some(address), _) => {
+            let (name, abi) = etherscan::fetch_abi(
+                address,
+                options.chain_id,
+                options.etherscan_key.as_deref(),
+                options.contract_name.as_deref(),
+            )?;
+            (vec![contract_data(&name, abi, options)], Vec::new())
+        }
+        (None, None, None) if options.abi_dir.is_some() => {
+            let abi_files = find_abi_files(Path::new(options.abi_dir.as_deref().unwrap_or_default()), filter, options)?;
+            contract_files = Some(abi_files.len());
+            abi_dir_contracts(&abi_files, options)
+        }
+        (None, None, None) => {
+            let overrides = read_abi_overrides(options)?;
+            let sol_files = match &options.files_from {
+                _ if !options.qualified_names.is_empty() => qualified_files(options)?,
+                Some(list) => read_file_list(list, filter, options)?,
+                None => folders_contract_files(filter, options)?,
+            };
+            if options.dry_run {
+                print_dry_run(&sol_files, options);
+                return Ok(0);
+            }
+            // Each forge project once, the current folder without contracts' folders
+            let roots: BTreeSet<&Path> = match options.project_roots.as_slice() {
+                _ if options.solc => BTreeSet::new(),
+                [] => BTreeSet::from([Path::new("")]),
+                roots => roots.iter().map(PathBuf::as_path).collect(),
+            };
+            if options.solc {
+                solc_versions.extend(check_solc()?);
+            }
+            for root in roots {
+                let artifacts = root.join(ARTIFACTS_DIR);
+                let compiled = if !options.no_compile {
+                    compile_contracts(root, options.allow_compile_errors)?
+                } else if !options.use_inspect && !artifacts.is_dir() {
+                    // 'forge inspect' compiles on its own, only the artifacts need 'out/'.
+                    bail!(
+                        "No '{}' folder with compiled artifacts, run 'forge compile' or drop '--no-compile'",
+                        artifacts.display()
+                    );
+                } else {
+                    Vec::new()
+                };
+                // Nothing was compiled if no file changed, the version can still be pinned in the config
+                if compiled.is_empty() && (options.summary_json.is_some() || options.solc_comment) {
+                    solc_versions.extend(forge_config_solc(root));
+                }
+                solc_versions.extend(compiled);
+            }
+            contract_files = Some(sol_files.len());
+            let (contracts, failed) = scan_contracts(sol_files, &overrides, options, progress);
+            for name in overrides.keys().filter(|name| !contracts.iter().any(|c| &c.name == *name)) {
+                warn!("No contract '{}' found, its ABI override isn't used", name);
+            }
+            // The contracts were asked for by name, one that doesn't resolve isn't skipped
+            if let (false, Some(failure)) = (options.qualified_names.is_empty(), failed.first()) {
+                bail!("A contract of '--contract' in {:?} couldn't be resolved: {}", failure.path, failure.error);
+            }
+            (contracts, failed)
+        }
+    };
+    // Functions of a contract can't share a selector, the ABI is wrong
+    let mut duplicates = 0;
+    for contract in &contracts {
+        for (selector, signature_a, signature_b) in contract.duplicate_selectors() {
+            let message = format!(
+                "Contract '{}' has two functions with the selector {}: '{}' and '{}'",
+                contract.name, selector, signature_a, signature_b
             );
-            writer.write_all(line.as_bytes()).expect("Error writting on selector's CSV file");
+            if options.strict {
+                error!("{}", message);
+            } else {
+                warn!("{}", message);
+            }
+            duplicates += 1;
+        }
+    }
+    if duplicates > 0 && options.strict {
+        bail!("{} selector(s) shared by different functions of the same contract", duplicates);
+    }
+    // Receive and fallback have no selector, they never match
+    if let Some(prefix) = &options.prefix_filter {
+        for contract in &mut contracts {
+            contract.functions.retain(|f| f.selector.starts_with(prefix.as_str()));
+        }
+        let kept: usize = contracts.iter().map(|c| c.functions.len()).sum();
+        info!("{} function(s) with a selector starting with {}", kept, prefix);
+    }
+    if options.min_indexed > 0 {
+        for contract in &mut contracts {
+            contract.events.retain(|e| e.indexed.len() >= options.min_indexed);
+        }
+        let kept: usize = contracts.iter().map(|c| c.events.len()).sum();
+        info!("{} event(s) with at least {} indexed parameter(s)", kept, options.min_indexed);
+    }
+    // Sorted by name (and each contract's entries by signature) so repeated runs produce identical files.
+    if !options.no_sort {
+        contracts.sort_by(|a, b| a.name.cmp(&b.name));
+        for contract in &mut contracts {
+            contract.sort();
+        }
+    }
+    if options.group_by_mutability {
+        for contract in &mut contracts {
+            contract.group_functions();
+        }
+    }
+
+    let solc_versions: Vec<String> = solc_versions.into_iter().collect();
+    if let Some(summary_path) = &options.summary_json {
+        output::write_summary(Path::new(summary_path), &contracts, &failed, &solc_versions)?;
+    }
+    let csv_comment = match (options.solc_comment, solc_versions.is_empty()) {
+        (false, _) => None,
+        (true, true) => {
+            warn!("The solc version couldn't be determined, the CSV files are written without '# solc' line");
+            None
+        }
+        (true, false) => Some(format!("solc {}", solc_versions.join(", "))),
+    };
+
+    let mismatches = if options.verify { verify_selectors(&contracts)? } else { 0 };
+
+    if options.lookup_4byte {
+        let mut client = FourByteClient::new();
+        for function in contracts.iter_mut().flat_map(|c| &mut c.functions).filter(|f| !f.selector.is_empty()) {
+            function.known_signatures = Some(client.lookup(&function.selector));
+        }
+    }
+    if options.flag_known {
+        for function in contracts.iter_mut().flat_map(|c| &mut c.functions).filter(|f| !f.selector.is_empty()) {
+            function.known_as = known_annotation(function);
         }
     }
-    println!("CSV files generated:\n  Events -> {:?}\n  Selectors -> {:?}", events_csv_path, selectors_csv_path);
-}
\ No newline at end of file
+
+    if let Some(hash) = &options.lookup {
+        if output::print_lookup(&contracts, hash) == 0 {
+            bail!("No signature found for {}", hash);
+        }
+        return Ok(0);
+    }
+
+    // Only now, the selectors are looked up and checked with their prefix above
+    if options.no_0x {
+        for contract in &mut contracts {
+            contract.strip_hex_prefix();
+        }
+    }
+    if let Some(manifest_path) = &options.manifest {
+        output::write_manifest(Path::new(manifest_path), &contracts)?;
+    }
+
+    if options.format == OutputFormat::TopicsOnly {
+        let topics = output::topics_table(&contracts);
+        if options.stdout {
+            output::write_stdout(&[("topics", &topics)], options.delimiter)?;
+        } else {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let path = output_dir.join("topics.csv");
+            output::write_csv(&path, &topics, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", path))?;
+            println!("CSV file generated:\n  Topics -> {:?}", path);
+        }
+    } else if options.format == OutputFormat::Cast {
+        let lines = output::cast_lines(&contracts);
+        if options.stdout {
+            output::write_lines_stdout(&lines)?;
+        } else {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let path = output_dir.join("signatures.txt");
+            output::write_file(&path, &lines.iter().map(|line| format!("{}\n", line)).collect::<String>())?;
+            println!("Text file generated:\n  Signatures -> {:?}", path);
+        }
+    } else if options.stdout && options.combined {
+        output::write_stdout(&[("signatures", &output::combined_table(&contracts))], options.delimiter)?;
+    } else if options.stdout {
+        let tables = output::csv_tables(&contracts, options);
+        let sections: Vec<_> = tables.sections().into_iter().filter(|(kind, _)| options.wants(kind)).collect();
+        output::write_stdout(&sections, options.delimiter)?;
+    } else if options.format == OutputFormat::Html {
+        create_dir_all(output_dir).context("Output folder couldn't be created")?;
+        let path = output_dir.join("report.html");
+        output::write_file(&path, &html::report(&contracts))?;
+        println!("HTML file generated:\n  Report -> {:?}", path);
+    } else if options.format == OutputFormat::Sqlite {
+        let path = match &options.db {
+            Some(db) => PathBuf::from(db),
+            None => {
+                create_dir_all(output_dir).context("Output folder couldn't be created")?;
+                output_dir.join("selectors.db")
+            }
+        };
+        sqlite::write_database(&path, &contracts)?;
+        println!("SQLite database generated:\n  Selectors -> {:?}", path);
+    } else if options.combined {
+        create_dir_all(output_dir).context("Output folder couldn't be created")?;
+        let extension = options.format.extension();
+        let path = output_dir.join(format!("signatures.{}", extension));
+        match options.format {
+            OutputFormat::Csv => {
+                let rows = output::combined_table(&contracts);
+                output::write_commented_csv(&path, csv_comment.as_deref(), &rows, options.delimiter)
+                    .with_context(|| format!("Error writting on {:?}", path))?
+            }
+            OutputFormat::Json | OutputFormat::Jsonl => {
+                output::write_combined_json(&path, &contracts, options.format == OutputFormat::Jsonl)?
+            }
+            OutputFormat::Html | OutputFormat::TopicsOnly | OutputFormat::Cast | OutputFormat::Sqlite => {
+                unreachable!("checked by parse_args")
+            }
+        }
+        println!("{} file generated:\n  Signatures -> {:?}", extension.to_uppercase(), path);
+    } else {
+        // Finnaly, we write output files in their respective sub-folders
+        let extension = options.format.extension();
+        let path = |kind| options.wants(kind).then(|| kind_path(output_dir, kind, options));
+        let paths = OutputPaths {
+            events: path("events"),
+            selectors: path("selectors"),
+            errors: path("errors"),
+            constructors: path("constructors"),
+        };
+        // Only the folders actually written into
+        for (_, path) in paths.files() {
+            let dir = path.parent().unwrap_or(output_dir);
+            create_dir_all(dir).with_context(|| format!("Folder {:?} couldn't be created", dir))?;
+        }
+
+        match options.format {
+            OutputFormat::Csv => {
+                let tables = output::csv_tables(&contracts, options);
+                output::write_tables(&paths, &tables, csv_comment.as_deref(), options.delimiter)?
+            }
+            OutputFormat::Json | OutputFormat::Jsonl => output::write_json_output(&paths, &contracts, options)?,
+            OutputFormat::Html | OutputFormat::TopicsOnly | OutputFormat::Cast | OutputFormat::Sqlite => {
+                unreachable!("written above")
+            }
+        }
+        println!("{} files generated:", extension.to_uppercase());
+        for (label, path) in paths.files() {
+            println!("  {} -> {:?}", label, path);
+        }
+    }
+
+    if options.emit_interface {
+        let interfaces_dir = if options.flat { output_dir.to_path_buf() } else { output_dir.join("interfaces") };
+        create_dir_all(&interfaces_dir).context("'interfaces' folder couldn't be created")?;
+        output::write_interfaces(&interfaces_dir, &contracts)?;
+        println!("  Interfaces -> {:?}", interfaces_dir);
+    }
+
+    // Write individual .txt files (optional), next to the tables
+    if options.txt {
+        let selectors_dir = options.wants("selectors").then(|| kind_dir(output_dir, "selectors", options));
+        let events_dir = options.wants("events").then(|| kind_dir(output_dir, "events", options));
+        for dir in selectors_dir.iter().chain(&events_dir) {
+            create_dir_all(dir).with_context(|| format!("Folder {:?} couldn't be created", dir))?;
+        }
+        output::write_txt_files(selectors_dir.as_deref(), events_dir.as_deref(), &contracts)?;
+        let dirs: Vec<String> = selectors_dir.iter().chain(&events_dir).map(|dir| format!("{:?}", dir)).collect();
+        println!("  Txt files -> {}", dirs.join(" and "));
+    }
+
+    // Events' types split between topics and data (optional), next to the events
+    if options.event_layout {
+        let layouts = output::event_layouts_table(&contracts);
+        if options.stdout {
+            output::write_stdout(&[("event_layouts", &layouts)], options.delimiter)?;
+        } else {
+            let events_dir = kind_dir(output_dir, "events", options);
+            create_dir_all(&events_dir).with_context(|| format!("Folder {:?} couldn't be created", events_dir))?;
+            let layouts_path = events_dir.join("event_layouts.csv");
+            output::write_csv(&layouts_path, &layouts, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", layouts_path))?;
+            println!("  Event layouts -> {:?}", layouts_path);
+        }
+    }
+
+    if options.stats {
+        let stats = output::stats_table(&contracts);
+        if options.stdout {
+            output::write_stdout(&[("stats", &stats)], options.delimiter)?;
+        } else {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let stats_path = output_dir.join("stats.csv");
+            output::write_csv(&stats_path, &stats, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", stats_path))?;
+            println!("  Stats -> {:?}", stats_path);
+        }
+    }
+
+    if options.interface_id {
+        let ids = output::interface_ids_table(&contracts, if options.no_0x { "" } else { "0x" });
+        if options.stdout {
+            output::write_stdout(&[("interface_ids", &ids)], options.delimiter)?;
+        } else {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let ids_path = output_dir.join("interface_ids.csv");
+            output::write_csv(&ids_path, &ids, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", ids_path))?;
+            println!("  Interface IDs -> {:?}", ids_path);
+        }
+    }
+
+    if options.typehash {
+        let type_hashes = output::type_hashes_table(&contracts, if options.no_0x { "" } else { "0x" });
+        if options.stdout {
+            output::write_stdout(&[("typehashes", &type_hashes)], options.delimiter)?;
+        } else {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let path = output_dir.join("typehashes.csv");
+            output::write_csv(&path, &type_hashes, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", path))?;
+            println!("  Type hashes -> {:?}", path);
+        }
+    }
+
+    // Compare the functions with the ones of a previous run
+    let mut removed = 0;
+    if let Some(old_path) = &options.diff {
+        let only_processed = options.since.is_some();
+        let (diff_rows, removed_count) =
+            diff::selectors_diff(Path::new(old_path), &contracts, only_processed, options.no_0x, options.delimiter)?;
+        removed = removed_count;
+        for row in diff_rows.iter().skip(1).filter(|row| row[0] == "removed") {
+            warn!("Function removed from '{}': {} {}", row[1], row[3], row[2]);
+        }
+        info!("{} change(s) since {:?}", diff_rows.len() - 1, old_path);
+        if options.stdout {
+            output::write_stdout(&[("diff", &diff_rows)], options.delimiter)?;
+        } else {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let diff_path = output_dir.join("diff.csv");
+            output::write_csv(&diff_path, &diff_rows, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", diff_path))?;
+            println!("  Diff -> {:?}", diff_path);
+        }
+    }
+
+    // Look for different function signatures sharing the same selector across all contracts, the ones of
+    // '--from-bytecode' have no signature to compare
+    let collisions = find_collisions(
+        contracts
+            .iter()
+            .flat_map(|c| &c.functions)
+            .filter(|f| !f.selector.is_empty() && !f.signature.is_empty())
+            .map(|f| f.signature.as_str()),
+        &options.hasher,
+    );
+    let has_collisions = !collisions.is_empty();
+    if has_collisions {
+        let mut csv_collisions: Vec<Vec<String>> = Vec::new();
+        csv_collisions.push(vec!["selector".to_string(), "signatureA".to_string(), "signatureB".to_string()]);
+        for (selector, sig_a, sig_b) in collisions {
+            let selector_hex = format!("0x{}", hex::encode(&selector));
+            warn!("Selector collision {}: '{}' and '{}'", selector_hex, sig_a, sig_b);
+            let selector_hex = if options.no_0x { hex::encode(selector) } else { selector_hex };
+            csv_collisions.push(vec![selector_hex, sig_a, sig_b]);
+        }
+        if !options.stdout {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let collisions_path = output_dir.join("collisions.csv");
+            output::write_csv(&collisions_path, &csv_collisions, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", collisions_path))?;
+            println!("  Collisions -> {:?}", collisions_path);
+        }
+    }
+
+    // Selectors of the proxy's own functions shadowing the implementation's ones
+    let mut has_clashes = false;
+    if let (Some(proxy), Some(implementation)) = (&options.proxy, &options.implementation) {
+        let clashes = proxy_clashes(&contracts, proxy, implementation)?;
+        has_clashes = clashes.len() > 1;
+        if has_clashes && !options.stdout {
+            create_dir_all(output_dir).context("Output folder couldn't be created")?;
+            let clashes_path = output_dir.join("proxy_clashes.csv");
+            output::write_csv(&clashes_path, &clashes, options.delimiter)
+                .with_context(|| format!("Error writting on {:?}", clashes_path))?;
+            println!("  Proxy clashes -> {:?}", clashes_path);
+        }
+    }
+
+    if !failed.is_empty() && !options.stdout {
+        create_dir_all(output_dir).context("Output folder couldn't be created")?;
+        let failures_path = output_dir.join("failures.log");
+        output::write_file(&failures_path, &output::failures_log(&failed))?;
+        println!("  Failures -> {:?}", failures_path);
+    }
+
+    if has_collisions && options.fail_on_collision {
+        bail!("Selector collisions found");
+    }
+    if has_clashes && options.fail_on_collision {
+        bail!("Selector clashes found between the proxy and its implementation");
+    }
+    if removed > 0 && options.fail_on_removed {
+        bail!("{} function(s) removed since {:?}", removed, options.diff.as_deref().unwrap_or_default());
+    }
+    if mismatches > 0 {
+        bail!("{} selector(s) don't match 'cast sig'", mismatches);
+    }
+    if !failed.is_empty() {
+        bail!("{} contract(s) couldn't be processed", failed.len());
+    }
+    let entries: usize = contracts.iter().map(|c| c.functions.len() + c.events.len()).sum();
+    if entries == 0 && options.fail_on_empty {
+        match contract_files {
+            Some(0) => bail!(
+                "No contract files found: check the contracts' folder, '--include'/'--exclude' and '--max-depth'"
+            ),
+            Some(count) => bail!(
+                "{} contract file(s) found but no functions nor events: check that they're compiled, \
+                 and '--only', '--prefix-filter' or '--min-indexed'",
+                count
+            ),
+            None => bail!("No functions nor events found in the contract"),
+        }
+    }
+    let selectors = contracts.iter().flat_map(|c| &c.functions).filter(|f| !f.selector.is_empty()).count();
+    Ok(selectors + contracts.iter().map(|c| c.errors.len()).sum::<usize>())
+}
@@ -0,0 +1,806 @@
+//! Writing of the computed data: CSV or JSON files, or the standard output.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use log::{debug, warn};
+use serde::Serialize;
+
+use selector_calculator::{interface_id, safe_file_name, signature_hash};
+
+use crate::{
+    ConstructorRow, ContractData, ErrorRow, EventRow, Failure, FunctionRow, MutabilityGroup, Options, OutputFormat,
+    Parameter,
+};
+
+/// CSV rows, header included.
+pub type CsvRows = Vec<Vec<String>>;
+
+/// The CSV table of each kind.
+pub struct Tables {
+    pub events: CsvRows,
+    pub selectors: CsvRows,
+    pub errors: CsvRows,
+    pub constructors: CsvRows,
+}
+
+impl Tables {
+    /// Each table with its kind, in the order they're printed.
+    pub fn sections(&self) -> [(&str, &CsvRows); 4] {
+        [
+            ("selectors", &self.selectors),
+            ("events", &self.events),
+            ("errors", &self.errors),
+            ("constructors", &self.constructors),
+        ]
+    }
+}
+
+/// Output file of each kind, `None` for the kinds left out with `--only`.
+pub struct OutputPaths {
+    pub events: Option<PathBuf>,
+    pub selectors: Option<PathBuf>,
+    pub errors: Option<PathBuf>,
+    pub constructors: Option<PathBuf>,
+}
+
+impl OutputPaths {
+    /// Each file written with its label, in the order they're listed.
+    pub fn files(&self) -> Vec<(&str, &Path)> {
+        [
+            ("Events", &self.events),
+            ("Selectors", &self.selectors),
+            ("Errors", &self.errors),
+            ("Constructors", &self.constructors),
+        ]
+        .into_iter()
+        .filter_map(|(label, path)| path.as_deref().map(|path| (label, path)))
+        .collect()
+    }
+}
+
+/// A unique signature across all contracts (`--dedup`), with every contract defining it.
+#[derive(Serialize)]
+struct DedupRow {
+    signature: String,
+    hash: String,
+    contracts: Vec<String>,
+}
+
+/// A function, event or error in the combined output (`--combined`).
+#[derive(Serialize)]
+struct CombinedRow<'a> {
+    contract: &'a str,
+    kind: &'static str,
+    signature: &'a str,
+    // Selector, or topic for events.
+    hash: &'a str,
+}
+
+/// A function of the manifest (`--manifest`), under its selector.
+#[derive(Serialize)]
+struct ManifestEntry<'a> {
+    // 1st contract defining it, `contracts` lists all of them.
+    contract: &'a str,
+    contracts: Vec<&'a str>,
+    name: &'a str,
+    signature: &'a str,
+    inputs: &'a [Parameter],
+    #[serde(rename = "stateMutability")]
+    state_mutability: Option<&'a str>,
+}
+
+/// Counts of the run (`--summary-json`).
+#[derive(Serialize)]
+struct Summary<'a> {
+    contracts: usize,
+    functions: usize,
+    events: usize,
+    errors: usize,
+    failed_files: &'a [Failure],
+    // Empty if it couldn't be determined
+    solc_versions: &'a [String],
+}
+
+/// Write CSV rows to `writer`, one line per row, their fields separated by `delimiter` (`,` unless '--delimiter').
+/// Fields with the delimiter (e.g. tuples' signatures and comas), quotes or line breaks are quoted as in RFC 4180,
+/// lines end with `\n` only.
+pub fn write_csv_rows<W: Write>(writer: &mut W, rows: &[Vec<String>], delimiter: u8) -> io::Result<()> {
+    let mut csv_writer =
+        csv::WriterBuilder::new().delimiter(delimiter).terminator(csv::Terminator::Any(b'\n')).from_writer(writer);
+    for row in rows {
+        csv_writer.write_record(row)?;
+    }
+    csv_writer.flush()
+}
+
+/// Write `path` through a temporary file in the same folder, renamed into place once fully written,
+/// so readers never see a partial file. The temporary file is removed if anything fails.
+fn write_atomically<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        write(&mut writer)?;
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Write `content` to `path`, e.g. a report or an interface's source.
+pub fn write_file(path: &Path, content: &str) -> anyhow::Result<()> {
+    write_atomically(path, |writer| writer.write_all(content.as_bytes()))
+        .with_context(|| format!("Error writting on {:?}", path))
+}
+
+/// Write CSV rows to `path`, one line per row.
+pub fn write_csv(path: &Path, rows: &[Vec<String>], delimiter: u8) -> io::Result<()> {
+    write_commented_csv(path, None, rows, delimiter)
+}
+
+/// Write CSV rows to `path` like `write_csv`, after a `# <comment>` line if there is one (`--solc-comment`).
+pub fn write_commented_csv(path: &Path, comment: Option<&str>, rows: &[Vec<String>], delimiter: u8) -> io::Result<()> {
+    write_atomically(path, |writer| {
+        if let Some(comment) = comment {
+            writeln!(writer, "# {}", comment)?;
+        }
+        write_csv_rows(writer, rows, delimiter)
+    })
+}
+
+/// Write `value` (e.g. an array of rows) to `path` as pretty-printed JSON.
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> anyhow::Result<()> {
+    write_atomically(path, |writer| Ok(serde_json::to_writer_pretty(writer, value)?))
+        .with_context(|| format!("Error writting on {:?}", path))
+}
+
+fn header(columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|c| c.to_string()).collect()
+}
+
+/// Row with only the contract's name, the other `width - 1` columns left empty.
+fn name_row(name: &str, width: usize) -> Vec<String> {
+    let mut row = vec![String::new(); width];
+    row[0] = name.to_string();
+    row
+}
+
+/// CSV tables of all contracts, deduplicated or grouped by contract.
+pub fn csv_tables(contracts: &[ContractData], options: &Options) -> Tables {
+    if options.dedup {
+        dedup_tables(contracts)
+    } else {
+        grouped_tables(contracts, options)
+    }
+}
+
+/// Grouped CSV tables: a contract's name row followed by a row for each of its entries.
+fn grouped_tables(contracts: &[ContractData], options: &Options) -> Tables {
+    // Row for each entry: [contractName, <signature>, <selector or topic>]
+    // Events have an extra column with the indexed parameters' positions,
+    // functions with their state mutability (and optionally their full hash, known signatures and notable
+    // selector).
+    // Optionally, columns with the parameters' names, the position in the ABI, the contract's file and folder,
+    // and functions always end with whether they're payable.
+    let mut events = vec![header(&["contractName", "event", "topic", "indexed"])];
+    let mut selectors = vec![header(&["contractName", "function", "selector", "stateMutability"])];
+    let mut errors = vec![header(&["contractName", "error", "selector"])];
+    // Constructors keep the same columns, with an empty selector
+    let mut constructors = vec![header(&["contractName", "constructor", "selector"])];
+    if options.full_hash {
+        selectors[0].push("hash".to_string());
+    }
+    if options.lookup_4byte {
+        selectors[0].push("knownSignatures".to_string());
+    }
+    if options.flag_known {
+        selectors[0].push("knownAs".to_string());
+    }
+    if options.topics_layout {
+        events[0].extend(header(&["topic0", "topic1", "topic2", "topic3"]));
+    }
+    if options.with_names {
+        for table in [&mut events, &mut selectors, &mut errors] {
+            table[0].push("parameterNames".to_string());
+        }
+    }
+    if options.with_index {
+        for table in [&mut events, &mut selectors, &mut errors] {
+            table[0].push("abiIndex".to_string());
+        }
+    }
+    if options.with_source {
+        for table in [&mut events, &mut selectors, &mut errors, &mut constructors] {
+            table[0].push("source".to_string());
+        }
+    }
+    if options.merges_folders() {
+        for table in [&mut events, &mut selectors, &mut errors, &mut constructors] {
+            table[0].push("repo".to_string());
+        }
+    }
+    selectors[0].push("payable".to_string());
+
+    // Contracts with an empty ABI would only add a name row
+    for contract in contracts.iter().filter(|c| !c.is_empty()) {
+        // 1st row: contract's name
+        events.push(name_row(&contract.name, events[0].len()));
+        selectors.push(name_row(&contract.name, selectors[0].len()));
+        errors.push(name_row(&contract.name, errors[0].len()));
+        constructors.push(name_row(&contract.name, constructors[0].len()));
+        // Then, a row for each event (leaving 1st column empty)
+        for event in &contract.events {
+            let sig = if event.anonymous {
+                format!("{} [anonymous]", event.signature)
+            } else {
+                event.signature.clone()
+            };
+            // Indexed positions as e.g. "0;1"
+            let indexed = event.indexed.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(";");
+            let mut row = vec!["".to_string(), sig, event.topic.clone(), indexed];
+            if options.topics_layout {
+                row.extend(event.topics_layout.clone().unwrap_or_default());
+            }
+            if options.with_names {
+                row.push(event.names.clone().unwrap_or_default());
+            }
+            if options.with_index {
+                row.push(event.index.map(|i| i.to_string()).unwrap_or_default());
+            }
+            if options.with_source {
+                row.push(event.source.clone().unwrap_or_default());
+            }
+            if options.merges_folders() {
+                row.push(event.repo.clone().unwrap_or_default());
+            }
+            events.push(row);
+        }
+        // Then, a row for each function, with a comment row before each group of '--group-by-mutability'
+        let mut group = None;
+        for function in &contract.functions {
+            let function_group = MutabilityGroup::of(function.state_mutability.as_deref());
+            if options.group_by_mutability && group != Some(function_group) {
+                let mut comment = vec![String::new(); selectors[0].len()];
+                comment[1] = format!("# {}", function_group.label());
+                selectors.push(comment);
+                group = Some(function_group);
+            }
+            let mut row = vec![
+                "".to_string(),
+                function.signature.clone(),
+                function.selector.clone(),
+                function.state_mutability.clone().unwrap_or_default(),
+            ];
+            if options.full_hash {
+                row.push(function.hash.clone().unwrap_or_default());
+            }
+            if options.lookup_4byte {
+                row.push(function.known_signatures.clone().unwrap_or_default());
+            }
+            if options.flag_known {
+                row.push(function.known_as.clone().unwrap_or_default());
+            }
+            if options.with_names {
+                row.push(function.names.clone().unwrap_or_default());
+            }
+            if options.with_index {
+                row.push(function.index.map(|i| i.to_string()).unwrap_or_default());
+            }
+            if options.with_source {
+                row.push(function.source.clone().unwrap_or_default());
+            }
+            if options.merges_folders() {
+                row.push(function.repo.clone().unwrap_or_default());
+            }
+            row.push(function.payable.to_string());
+            selectors.push(row);
+        }
+        // Then, a row for each error
+        for error in &contract.errors {
+            let mut row = vec!["".to_string(), error.signature.clone(), error.selector.clone()];
+            if options.with_names {
+                row.push(error.names.clone().unwrap_or_default());
+            }
+            if options.with_index {
+                row.push(error.index.map(|i| i.to_string()).unwrap_or_default());
+            }
+            if options.with_source {
+                row.push(error.source.clone().unwrap_or_default());
+            }
+            if options.merges_folders() {
+                row.push(error.repo.clone().unwrap_or_default());
+            }
+            errors.push(row);
+        }
+        // Finally, the constructor (if any)
+        for constructor in &contract.constructors {
+            let mut row = vec!["".to_string(), constructor.signature.clone(), "".to_string()];
+            if options.with_source {
+                row.push(constructor.source.clone().unwrap_or_default());
+            }
+            if options.merges_folders() {
+                row.push(constructor.repo.clone().unwrap_or_default());
+            }
+            constructors.push(row);
+        }
+    }
+
+    if !options.columns.is_empty() {
+        selectors = select_columns(selectors, &options.columns);
+    }
+    Tables { events, selectors, errors, constructors }
+}
+
+/// Only the `columns` of a grouped table, in their order, with the contract's name on each row instead of a
+/// name row (`--columns`).
+fn select_columns(rows: CsvRows, columns: &[String]) -> CsvRows {
+    let positions: Vec<usize> = columns
+        .iter()
+        .map(|column| rows[0].iter().position(|c| c == column).expect("checked by parse_args"))
+        .collect();
+    let mut selected = vec![columns.to_vec()];
+    let mut contract = String::new();
+    for mut row in rows.into_iter().skip(1) {
+        // Name rows only have the contract's name, functions of '--from-bytecode' have no signature but a selector
+        if row[1..].iter().all(String::is_empty) {
+            contract = row.swap_remove(0);
+            continue;
+        }
+        row[0] = contract.clone();
+        selected.push(positions.iter().map(|&i| row[i].clone()).collect());
+    }
+    selected
+}
+
+/// Collapse `(signature, hash, contract)` rows into unique signatures sorted alphabetically.
+fn dedup_rows<'a, I>(rows: I) -> Vec<DedupRow>
+where
+    I: Iterator<Item = (&'a str, &'a str, &'a str)>,
+{
+    let mut unique: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+    for (signature, hash, contract) in rows {
+        let contracts = unique.entry((signature, hash)).or_default();
+        if !contracts.iter().any(|c| c == contract) {
+            contracts.push(contract.to_string());
+        }
+    }
+    unique
+        .into_iter()
+        .map(|((signature, hash), contracts)| DedupRow {
+            signature: signature.to_string(),
+            hash: hash.to_string(),
+            contracts,
+        })
+        .collect()
+}
+
+/// Unique events, functions, errors and constructors across all contracts.
+fn dedup_all(contracts: &[ContractData]) -> [Vec<DedupRow>; 4] {
+    let events = dedup_rows(
+        contracts.iter().flat_map(|c| &c.events).map(|e| (e.signature.as_str(), e.topic.as_str(), e.contract.as_str())),
+    );
+    let functions = dedup_rows(
+        contracts
+            .iter()
+            .flat_map(|c| &c.functions)
+            .map(|f| (f.signature.as_str(), f.selector.as_str(), f.contract.as_str())),
+    );
+    let errors = dedup_rows(
+        contracts.iter().flat_map(|c| &c.errors).map(|e| (e.signature.as_str(), e.selector.as_str(), e.contract.as_str())),
+    );
+    let constructors = dedup_rows(
+        contracts.iter().flat_map(|c| &c.constructors).map(|c| (c.signature.as_str(), "", c.contract.as_str())),
+    );
+    [events, functions, errors, constructors]
+}
+
+/// Deduplicated CSV tables (`--dedup`): one row per unique signature instead of grouping by contract.
+fn dedup_tables(contracts: &[ContractData]) -> Tables {
+    let to_csv = |rows: Vec<DedupRow>, columns: &[&str]| {
+        let mut csv_rows = vec![header(columns)];
+        // Contracts are semicolon-separated
+        csv_rows.extend(rows.into_iter().map(|r| vec![r.signature, r.hash, r.contracts.join(";")]));
+        csv_rows
+    };
+    let [events, functions, errors, constructors] = dedup_all(contracts);
+    Tables {
+        events: to_csv(events, &["event", "topic", "contracts"]),
+        selectors: to_csv(functions, &["function", "selector", "contracts"]),
+        errors: to_csv(errors, &["error", "selector", "contracts"]),
+        constructors: to_csv(constructors, &["constructor", "selector", "contracts"]),
+    }
+}
+
+/// Write each table to its file, if it has one, after the `comment` line.
+pub fn write_tables(paths: &OutputPaths, tables: &Tables, comment: Option<&str>, delimiter: u8) -> anyhow::Result<()> {
+    for (path, rows) in [
+        (&paths.events, &tables.events),
+        (&paths.selectors, &tables.selectors),
+        (&paths.errors, &tables.errors),
+        (&paths.constructors, &tables.constructors),
+    ] {
+        if let Some(path) = path {
+            write_commented_csv(path, comment, rows, delimiter)
+                .with_context(|| format!("Error writting on {:?}", path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Write tables to the standard output, each one preceded by a `# <kind>` line.
+pub fn write_stdout(sections: &[(&str, &CsvRows)], delimiter: u8) -> anyhow::Result<()> {
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    for (kind, rows) in sections {
+        writeln!(writer, "# {}", kind)?;
+        write_csv_rows(&mut writer, rows, delimiter)?;
+    }
+    writer.flush().context("Error writting on the standard output")
+}
+
+/// Topics of all contracts' events (`--format topics-only`) as `[topic, signature]` rows, without repetitions
+/// and sorted by signature. Anonymous events are left out, they have no topic in their logs.
+pub fn topics_table(contracts: &[ContractData]) -> CsvRows {
+    let topics: BTreeSet<(&str, &str)> = contracts
+        .iter()
+        .flat_map(|c| &c.events)
+        .filter(|e| !e.anonymous)
+        .map(|e| (e.signature.as_str(), e.topic.as_str()))
+        .collect();
+    let mut rows = vec![header(&["topic", "signature"])];
+    rows.extend(topics.into_iter().map(|(signature, topic)| vec![topic.to_string(), signature.to_string()]));
+    rows
+}
+
+/// Functions then events of all contracts (`--format cast`) as `signature hash` lines, e.g.
+/// `transfer(address,uint256) 0xa9059cbb`, without repetitions and sorted by signature. Functions without a
+/// selector (`--from-method-ids` misses) and anonymous events are left out.
+pub fn cast_lines(contracts: &[ContractData]) -> Vec<String> {
+    let functions: BTreeSet<(&str, &str)> = contracts
+        .iter()
+        .flat_map(|c| &c.functions)
+        .filter(|f| !f.selector.is_empty())
+        .map(|f| (f.signature.as_str(), f.selector.as_str()))
+        .collect();
+    let events: BTreeSet<(&str, &str)> = contracts
+        .iter()
+        .flat_map(|c| &c.events)
+        .filter(|e| !e.anonymous)
+        .map(|e| (e.signature.as_str(), e.topic.as_str()))
+        .collect();
+    functions.into_iter().chain(events).map(|(signature, hash)| format!("{} {}", signature, hash)).collect()
+}
+
+/// Write lines to the standard output, without any `# <kind>` line.
+pub fn write_lines_stdout(lines: &[String]) -> anyhow::Result<()> {
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+    writer.flush().context("Error writting on the standard output")
+}
+
+/// Size of each contract's surface (`--stats`) as `[contractName, functions, events, errors, payableFunctions]`
+/// rows, the contracts with the most functions first. Contracts with an empty ABI are left out like in the tables.
+pub fn stats_table(contracts: &[ContractData]) -> CsvRows {
+    let mut counts: Vec<(&str, usize, usize, usize, usize)> = contracts
+        .iter()
+        .filter(|c| !c.is_empty())
+        .map(|c| {
+            let payable = c.functions.iter().filter(|f| f.state_mutability.as_deref() == Some("payable")).count();
+            (c.name.as_str(), c.functions.len(), c.events.len(), c.errors.len(), payable)
+        })
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let mut rows = vec![header(&["contractName", "functions", "events", "errors", "payableFunctions"])];
+    rows.extend(counts.into_iter().map(|(name, functions, events, errors, payable)| {
+        vec![name.to_string(), functions.to_string(), events.to_string(), errors.to_string(), payable.to_string()]
+    }));
+    rows
+}
+
+/// ERC-165 interface ID of each contract having functions (`--interface-id`) as
+/// `[contractName, interfaceId, functions]` rows. `receive()` and `fallback()` have no selector and don't count.
+pub fn interface_ids_table(contracts: &[ContractData], hex_prefix: &str) -> CsvRows {
+    let mut rows = vec![header(&["contractName", "interfaceId", "functions"])];
+    for contract in contracts {
+        // Selectors are hex digits, with their '0x' unless '--no-0x'
+        let selectors: Vec<[u8; 4]> = contract
+            .functions
+            .iter()
+            .filter_map(|f| hex::decode(f.selector.trim_start_matches("0x")).ok()?.try_into().ok())
+            .collect();
+        if !selectors.is_empty() {
+            let id = hex::encode(interface_id(selectors.iter().copied()));
+            rows.push(vec![contract.name.clone(), format!("{}{}", hex_prefix, id), selectors.len().to_string()]);
+        }
+    }
+    rows
+}
+
+/// EIP-712 struct types of each contract (`--typehash`) as `[contractName, struct, encodedType, typeHash]` rows,
+/// the type hash being the keccak256 hash of the encoded type, e.g. a `PERMIT_TYPEHASH`.
+pub fn type_hashes_table(contracts: &[ContractData], hex_prefix: &str) -> CsvRows {
+    let mut rows = vec![header(&["contractName", "struct", "encodedType", "typeHash"])];
+    for contract in contracts {
+        for (name, encoded_type) in &contract.struct_types {
+            let hash = format!("{}{}", hex_prefix, hex::encode(signature_hash(encoded_type)));
+            rows.push(vec![contract.name.clone(), name.clone(), encoded_type.clone(), hash]);
+        }
+    }
+    rows
+}
+
+/// Layouts of all contracts' events (`--event-layout`) as `[signature, topic0, indexedTypes, dataTypes]` rows,
+/// types separated by `;`. Without repetitions and sorted by signature, anonymous events are left out like
+/// in `topics_table`.
+pub fn event_layouts_table(contracts: &[ContractData]) -> CsvRows {
+    let layouts: BTreeSet<(&str, &str, String, String)> = contracts
+        .iter()
+        .flat_map(|c| &c.events)
+        .filter(|e| !e.anonymous)
+        .map(|e| (e.signature.as_str(), e.topic.as_str(), e.indexed_types.join(";"), e.data_types.join(";")))
+        .collect();
+    let mut rows = vec![header(&["signature", "topic0", "indexedTypes", "dataTypes"])];
+    rows.extend(layouts.into_iter().map(|(signature, topic, indexed, data)| {
+        vec![signature.to_string(), topic.to_string(), indexed, data]
+    }));
+    rows
+}
+
+/// Functions, events, errors and constructors of all contracts in a single list, contract by contract.
+fn combined_rows(contracts: &[ContractData]) -> Vec<CombinedRow<'_>> {
+    let mut rows = Vec::new();
+    for contract in contracts {
+        let functions = contract.functions.iter().map(|f| ("function", f.signature.as_str(), f.selector.as_str()));
+        let events = contract.events.iter().map(|e| ("event", e.signature.as_str(), e.topic.as_str()));
+        let errors = contract.errors.iter().map(|e| ("error", e.signature.as_str(), e.selector.as_str()));
+        // Constructors have no selector
+        let constructors = contract.constructors.iter().map(|c| ("constructor", c.signature.as_str(), ""));
+        let all = functions.chain(events).chain(errors).chain(constructors);
+        rows.extend(all.map(|(kind, signature, hash)| CombinedRow {
+            contract: &contract.name,
+            kind,
+            signature,
+            hash,
+        }));
+    }
+    rows
+}
+
+/// Combined CSV table (`--combined`): a row per function, event, error and constructor, tagged with its kind.
+pub fn combined_table(contracts: &[ContractData]) -> CsvRows {
+    let mut rows = vec![header(&["contractName", "kind", "signature", "hash"])];
+    rows.extend(combined_rows(contracts).into_iter().map(|r| {
+        vec![r.contract.to_string(), r.kind.to_string(), r.signature.to_string(), r.hash.to_string()]
+    }));
+    rows
+}
+
+/// Write `rows` to `path` as JSON Lines (`--format jsonl`): an object per line with its keys sorted, the lines
+/// sorted by contract and signature, so that a committed report only changes where the contracts did.
+fn write_json_lines<T: Serialize>(path: &Path, rows: &[T]) -> anyhow::Result<()> {
+    // serde_json's objects are BTreeMaps, their keys are written sorted
+    let mut lines = rows
+        .iter()
+        .map(|row| {
+            let value = serde_json::to_value(row)?;
+            let key = |field: &str| value[field].as_str().unwrap_or_default().to_string();
+            Ok((key("contract"), key("signature"), value.to_string()))
+        })
+        .collect::<serde_json::Result<Vec<_>>>()
+        .with_context(|| format!("Error converting the rows of {:?}", path))?;
+    lines.sort();
+    write_atomically(path, |writer| {
+        for (_, _, line) in &lines {
+            writeln!(writer, "{}", line)?;
+        }
+        Ok(())
+    })
+    .with_context(|| format!("Error writting on {:?}", path))
+}
+
+/// Write the combined JSON file (`--combined --format json`), or JSON Lines one with `lines`.
+pub fn write_combined_json(path: &Path, contracts: &[ContractData], lines: bool) -> anyhow::Result<()> {
+    if lines {
+        write_json_lines(path, &combined_rows(contracts))
+    } else {
+        write_json(path, &combined_rows(contracts))
+    }
+}
+
+/// Write a kind's JSON (or JSON Lines) file, unless it's left out.
+fn write_kind_json<T: Serialize>(path: &Option<PathBuf>, rows: &[T], lines: bool) -> anyhow::Result<()> {
+    match path {
+        Some(path) if lines => write_json_lines(path, rows),
+        Some(path) => write_json(path, rows),
+        None => Ok(()),
+    }
+}
+
+/// Write the JSON files: a flat array of objects per kind, each tagged with its contract
+/// (or with every contract defining it when deduplicated). With `--format jsonl`, an object per line instead.
+pub fn write_json_output(paths: &OutputPaths, contracts: &[ContractData], options: &Options) -> anyhow::Result<()> {
+    let lines = options.format == OutputFormat::Jsonl;
+    if options.dedup {
+        let [events, functions, errors, constructors] = dedup_all(contracts);
+        write_kind_json(&paths.events, &events, lines)?;
+        write_kind_json(&paths.selectors, &functions, lines)?;
+        write_kind_json(&paths.errors, &errors, lines)?;
+        write_kind_json(&paths.constructors, &constructors, lines)
+    } else {
+        let events: Vec<&EventRow> = contracts.iter().flat_map(|c| &c.events).collect();
+        let functions: Vec<&FunctionRow> = contracts.iter().flat_map(|c| &c.functions).collect();
+        let errors: Vec<&ErrorRow> = contracts.iter().flat_map(|c| &c.errors).collect();
+        let constructors: Vec<&ConstructorRow> = contracts.iter().flat_map(|c| &c.constructors).collect();
+        write_kind_json(&paths.events, &events, lines)?;
+        write_kind_json(&paths.selectors, &functions, lines)?;
+        write_kind_json(&paths.errors, &errors, lines)?;
+        write_kind_json(&paths.constructors, &constructors, lines)
+    }
+}
+
+/// Print the functions and errors whose selector (or the events whose topic) is `hash`.
+/// Returns the number of matches.
+/// The matches are aligned in columns, with the hash colored when printing to a terminal (unless `NO_COLOR` is set).
+pub fn print_lookup(contracts: &[ContractData], hash: &str) -> usize {
+    let mut rows: Vec<(&str, &str)> = Vec::new();
+    for contract in contracts {
+        let functions = contract.functions.iter().map(|f| (&f.signature, &f.selector));
+        let errors = contract.errors.iter().map(|e| (&e.signature, &e.selector));
+        let events = contract.events.iter().map(|e| (&e.signature, &e.topic));
+        for (signature, _) in functions.chain(errors).chain(events).filter(|(_, h)| h.as_str() == hash) {
+            rows.push((&contract.name, signature));
+        }
+    }
+    if rows.is_empty() {
+        return 0;
+    }
+
+    let color = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty());
+    let hash_cell = if color { format!("\x1b[1;32m{}\x1b[0m", hash) } else { hash.to_string() };
+    let contract_width = rows.iter().map(|(contract, _)| contract.chars().count()).max().unwrap_or(0).max(8);
+    let signature_width = rows.iter().map(|(_, signature)| signature.chars().count()).max().unwrap_or(0).max(9);
+    println!("{:<contract_width$}  {:<signature_width$}  Selector", "Contract", "Signature");
+    for (contract, signature) in &rows {
+        println!("{:<contract_width$}  {:<signature_width$}  {}", contract, signature, hash_cell);
+    }
+    rows.len()
+}
+
+/// Write each contract's Solidity interface (`--emit-interface`) as `Interface<Contract>.sol` in `dir`.
+pub fn write_interfaces(dir: &Path, contracts: &[ContractData]) -> anyhow::Result<()> {
+    for contract in contracts {
+        if let Some(interface) = &contract.interface {
+            let path = dir.join(format!("Interface{}.sol", safe_file_name(&contract.name)));
+            write_file(&path, interface)?;
+        }
+    }
+    Ok(())
+}
+
+/// Content of `failures.log`: each file that couldn't be processed, followed by its error indented (with the command
+/// that failed and its exit code when it's a tool's failure).
+pub fn failures_log(failed: &[Failure]) -> String {
+    let mut log = String::new();
+    for failure in failed {
+        log.push_str(&format!("{}\n", failure.path.display()));
+        for line in failure.error.lines() {
+            log.push_str(&format!("    {}\n", line));
+        }
+    }
+    log
+}
+
+/// Write the summary of the run (`--summary-json`): how many contracts, functions, events and errors
+/// were found, the path and error of each contract that couldn't be processed, and the solc versions used.
+pub fn write_summary(
+    path: &Path,
+    contracts: &[ContractData],
+    failed: &[Failure],
+    solc_versions: &[String],
+) -> anyhow::Result<()> {
+    let summary = Summary {
+        contracts: contracts.len(),
+        functions: contracts.iter().map(|c| c.functions.len()).sum(),
+        events: contracts.iter().map(|c| c.events.len()).sum(),
+        errors: contracts.iter().map(|c| c.errors.len()).sum(),
+        failed_files: failed,
+        solc_versions,
+    };
+    write_json(path, &summary)
+}
+
+/// Write the manifest of the functions (`--manifest`) for documentation generators: each selector with its
+/// function's name, parameters and state mutability. A function defined by several contracts is listed once,
+/// and when two signatures have the same selector only the 1st one is kept.
+pub fn write_manifest(path: &Path, contracts: &[ContractData]) -> anyhow::Result<()> {
+    let mut manifest: BTreeMap<&str, ManifestEntry> = BTreeMap::new();
+    for function in contracts.iter().flat_map(|c| &c.functions).filter(|f| !f.selector.is_empty()) {
+        match manifest.get_mut(function.selector.as_str()) {
+            Some(entry) if entry.signature == function.signature => entry.contracts.push(&function.contract),
+            Some(entry) => warn!(
+                "'{}' of '{}' left out of the manifest, its selector {} is the one of '{}'",
+                function.signature, function.contract, function.selector, entry.signature
+            ),
+            None => {
+                let entry = ManifestEntry {
+                    contract: &function.contract,
+                    contracts: vec![&function.contract],
+                    name: function.signature.split('(').next().unwrap_or_default(),
+                    signature: &function.signature,
+                    inputs: &function.inputs,
+                    state_mutability: function.state_mutability.as_deref(),
+                };
+                manifest.insert(&function.selector, entry);
+            }
+        }
+    }
+    write_json(path, &manifest)
+}
+
+/// Write `signature -> hash` lines to `path`.
+fn write_txt<'a, I>(path: &Path, lines: I) -> anyhow::Result<()>
+where
+    I: Iterator<Item = (&'a str, &'a str)>,
+{
+    let write = |writer: &mut BufWriter<File>| -> io::Result<()> {
+        for (signature, hash) in lines {
+            writeln!(writer, "{} -> {}", signature, hash)?;
+        }
+        Ok(())
+    };
+    write_atomically(path, write).with_context(|| format!("Error writting on {:?}", path))
+}
+
+/// Write the individual .txt files (`--txt`): `<Contract>.txt` in `selectors_dir` with a `signature -> selector`
+/// line per function, and in `events_dir` with a `signature -> topic` line per event.
+/// Contracts sharing the same file name (from different files) get a `-2`, `-3`, ... suffix.
+/// A kind left out with `--only` has no folder.
+pub fn write_txt_files(
+    selectors_dir: Option<&Path>,
+    events_dir: Option<&Path>,
+    contracts: &[ContractData],
+) -> anyhow::Result<()> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for contract in contracts.iter().filter(|c| !c.is_empty()) {
+        let name = safe_file_name(&contract.name);
+        let count = seen.entry(name.clone()).or_insert(0);
+        *count += 1;
+        let file_name = match *count {
+            1 => format!("{}.txt", name),
+            n => format!("{}-{}.txt", name, n),
+        };
+        if let Some(selectors_dir) = selectors_dir {
+            // receive() and fallback() have no selector to write
+            let functions = contract
+                .functions
+                .iter()
+                .filter(|f| !f.selector.is_empty())
+                .map(|f| (f.signature.as_str(), f.selector.as_str()));
+            write_txt(&selectors_dir.join(&file_name), functions)?;
+        }
+        if let Some(events_dir) = events_dir {
+            // Anonymous events have no topic to write
+            let events = contract
+                .events
+                .iter()
+                .filter(|e| !e.topic.is_empty())
+                .map(|e| (e.signature.as_str(), e.topic.as_str()));
+            write_txt(&events_dir.join(&file_name), events)?;
+        }
+        debug!("txt files successfully written for '{}'", contract.name);
+    }
+    Ok(())
+}
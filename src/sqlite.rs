@@ -0,0 +1,78 @@
+//! SQLite database of the selectors (`--format sqlite`), updated in place by each run.
+
+use std::path::Path;
+
+use anyhow::Context;
+use log::debug;
+use rusqlite::{params, Connection};
+
+use crate::ContractData;
+
+/// Tables, each entry unique by contract and signature. `created_at` is kept from the 1st run listing the entry.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS functions (
+    contract TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    selector TEXT NOT NULL,
+    state_mutability TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (contract, signature)
+);
+CREATE INDEX IF NOT EXISTS functions_selector ON functions (selector);
+CREATE TABLE IF NOT EXISTS events (
+    contract TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    anonymous INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (contract, signature)
+);
+CREATE INDEX IF NOT EXISTS events_topic ON events (topic);
+CREATE TABLE IF NOT EXISTS errors (
+    contract TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    selector TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (contract, signature)
+);
+CREATE INDEX IF NOT EXISTS errors_selector ON errors (selector);
+";
+
+/// Create the tables at `path` if needed and upsert every contract's functions, events and errors.
+pub fn write_database(path: &Path, contracts: &[ContractData]) -> anyhow::Result<()> {
+    let write = || -> rusqlite::Result<()> {
+        let mut connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        // A single transaction, so a failed run leaves the database as it was
+        let transaction = connection.transaction()?;
+        {
+            let mut functions = transaction.prepare(
+                "INSERT INTO functions (contract, signature, selector, state_mutability) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (contract, signature)
+                 DO UPDATE SET selector = excluded.selector, state_mutability = excluded.state_mutability",
+            )?;
+            let mut events = transaction.prepare(
+                "INSERT INTO events (contract, signature, topic, anonymous) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (contract, signature) DO UPDATE SET topic = excluded.topic, anonymous = excluded.anonymous",
+            )?;
+            let mut errors = transaction.prepare(
+                "INSERT INTO errors (contract, signature, selector) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (contract, signature) DO UPDATE SET selector = excluded.selector",
+            )?;
+            for contract in contracts {
+                for f in &contract.functions {
+                    functions.execute(params![f.contract, f.signature, f.selector, f.state_mutability])?;
+                }
+                for e in &contract.events {
+                    events.execute(params![e.contract, e.signature, e.topic, e.anonymous])?;
+                }
+                for e in &contract.errors {
+                    errors.execute(params![e.contract, e.signature, e.selector])?;
+                }
+                debug!("'{}' written in the database", contract.name);
+            }
+        }
+        transaction.commit()
+    };
+    write().with_context(|| format!("Error writting on the database {:?}", path))
+}
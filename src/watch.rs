@@ -0,0 +1,77 @@
+//! Watch mode (`--watch`): the whole run is done again each time a contract file changes.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use log::{debug, error, info, warn};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+/// Time without any change to wait for before running again, so that several saves in a row make a single run.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Call `run` once, then again after each change of a file under `contracts_path` accepted by `is_watched`.
+/// `run` returns the number of selectors computed, its errors are logged and don't stop the watch.
+pub fn watch(
+    contracts_path: &Path,
+    is_watched: impl Fn(&Path) -> bool,
+    mut run: impl FnMut() -> anyhow::Result<usize>,
+) -> anyhow::Result<()> {
+    let watched_dir = std::fs::canonicalize(contracts_path)
+        .with_context(|| format!("Contracts' folder {:?} couldn't be read", contracts_path))?;
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).context("File watcher couldn't be created")?;
+    watcher
+        .watch(&watched_dir, RecursiveMode::Recursive)
+        .with_context(|| format!("Folder {:?} couldn't be watched", contracts_path))?;
+    loop {
+        match run() {
+            Ok(count) => println!("Updated {} selectors", count),
+            Err(err) => error!("{:#}", err),
+        }
+        info!("Watching {:?} for changes (Ctrl+C to stop)", contracts_path);
+        // Event paths are made relative to the contracts' folder as given, like the walked ones
+        let changed = wait_for_changes(&receiver, |path| {
+            path.strip_prefix(&watched_dir).is_ok_and(|relative| is_watched(&contracts_path.join(relative)))
+        })?;
+        for path in &changed {
+            debug!("{:?} changed", path);
+        }
+        info!("{} file(s) changed, running again", changed.len());
+    }
+}
+
+/// Block until a watched file changes, then until no other event comes for `DEBOUNCE`.
+fn wait_for_changes(
+    receiver: &Receiver<notify::Result<Event>>,
+    is_watched: impl Fn(&Path) -> bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut changed: Vec<PathBuf> = Vec::new();
+    loop {
+        let event = if changed.is_empty() {
+            receiver.recv().ok()
+        } else {
+            match receiver.recv_timeout(DEBOUNCE) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) => return Ok(changed),
+                Err(RecvTimeoutError::Disconnected) => None,
+            }
+        };
+        let Some(event) = event else {
+            bail!("File watcher stopped");
+        };
+        match event {
+            // Reading a file (e.g. by forge) isn't a change
+            Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) => {
+                for path in event.paths {
+                    if is_watched(&path) && !changed.contains(&path) {
+                        changed.push(path);
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(err) => warn!("File watcher error: {}", err),
+        }
+    }
+}
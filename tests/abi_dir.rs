@@ -0,0 +1,35 @@
+//! Contracts read from a folder of raw ABI files with `--abi-dir`, without forge.
+
+use std::process::Command;
+
+mod common;
+
+#[test]
+fn each_abi_file_is_a_contract() {
+    let dir = common::temp_path("abi_dir");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("vaults")).unwrap();
+    std::fs::write(dir.join("Token.json"), r#"[{ "type": "function", "name": "mint", "inputs": [] }]"#).unwrap();
+    std::fs::write(dir.join("vaults/Vault.json"), r#"[{ "type": "function", "name": "deposit", "inputs": [] }]"#)
+        .unwrap();
+    // Neither is an ABI
+    std::fs::write(dir.join("package.json"), r#"{ "name": "abis", "version": "1.0.0" }"#).unwrap();
+    std::fs::write(dir.join("README.md"), "Exported ABIs\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["--abi-dir", dir.to_str().unwrap(), "--stdout", "--only", "functions"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "# selectors\n\
+         contractName,function,selector,stateMutability,payable\n\
+         Token,,,,\n\
+         ,mint(),0x1249c58b,,false\n\
+         Vault,,,,\n\
+         ,deposit(),0xd0e30db0,,false\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("package.json\", it isn't an ABI"));
+}
@@ -0,0 +1,39 @@
+//! ABI files read instead of forge's, for the contracts they're given for.
+
+use std::process::Command;
+
+mod common;
+
+#[test]
+fn override_replaces_the_artifact() {
+    let root = common::temp_path("override");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/Token.sol"), "contract Token {}\n").unwrap();
+    std::fs::create_dir_all(root.join("out/Token.sol")).unwrap();
+    let artifact = r#"{ "abi": [{ "type": "function", "name": "mint", "inputs": [] }] }"#;
+    std::fs::write(root.join("out/Token.sol/Token.json"), artifact).unwrap();
+    // Not compiled at all, its name comes from the source
+    std::fs::write(root.join("src/Broken.sol"), "contract Broken { function f( }\n").unwrap();
+    let abi = r#"[{ "type": "function", "name": "burn", "stateMutability": "nonpayable", "inputs": [] }]"#;
+    std::fs::write(root.join("Broken.json"), abi).unwrap();
+
+    let run = |override_path: &str| {
+        Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+            .current_dir(&root)
+            .args(["-q", "--no-compile", "src", "-", "--only", "functions", "--abi-override", override_path])
+            .output()
+            .expect("the binary runs")
+    };
+    let output = run("Broken=Broken.json");
+    std::fs::write(root.join("Invalid.json"), "[{").unwrap();
+    let invalid = run("Broken=Invalid.json");
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Broken,,,,\n,burn(),0x44df8e70,nonpayable,false\n"), "{}", stdout);
+    assert!(stdout.contains("Token,,,,\n,mint(),0x1249c58b,"), "{}", stdout);
+    assert!(!invalid.status.success());
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("ABI override"));
+}
@@ -0,0 +1,68 @@
+//! Validation of the ABI types, flagging the bogus ones.
+
+use selector_calculator::{invalid_types, resolved_types, selector_for, signature_from_abi, AbiEntry};
+
+fn function(types: &[&str]) -> AbiEntry {
+    let inputs: Vec<String> = types.iter().map(|t| format!(r#"{{ "name": "", "type": "{}" }}"#, t)).collect();
+    let json = format!(r#"{{ "type": "function", "name": "f", "inputs": [{}] }}"#, inputs.join(","));
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn valid_types() {
+    let valid = [
+        "address", "bool", "string", "bytes", "function", "uint", "int", "uint8", "uint256", "int128", "bytes1",
+        "bytes32", "fixed", "ufixed", "fixed128x18", "ufixed8x0", "uint256[]", "bytes32[2][]", "address[3]",
+        "uint8[10]",
+    ];
+    assert!(invalid_types(&function(&valid)).is_empty());
+}
+
+#[test]
+fn invalid_types_are_listed() {
+    let types = ["uint257", "uint7", "int0", "uint08", "bytes0", "bytes33", "fixed128x81", "fixed128", "addr", "uint256[x]", "uint256[2"];
+    assert_eq!(invalid_types(&function(&types)), types);
+}
+
+#[test]
+fn zero_length_arrays_are_invalid() {
+    let types = ["uint256[0]", "address[0][]", "bytes32[2][0]", "bool[01]"];
+    assert_eq!(invalid_types(&function(&types)), types);
+}
+
+#[test]
+fn tuple_components_are_checked() {
+    let json = r#"{ "type": "event", "name": "E", "inputs": [
+        { "name": "t", "type": "tuple[]", "components": [
+            { "name": "a", "type": "uint256" },
+            { "name": "b", "type": "bytes33" }
+        ] }
+    ] }"#;
+    let entry: AbiEntry = serde_json::from_str(json).unwrap();
+    assert_eq!(invalid_types(&entry), ["bytes33"]);
+}
+
+#[test]
+fn unresolved_solidity_types_are_canonicalized() {
+    let json = r#"{ "type": "function", "name": "approve", "inputs": [
+        { "name": "spender", "type": "address payable" },
+        { "name": "amount", "type": "uint256" }
+    ] }"#;
+    let entry: AbiEntry = serde_json::from_str(json).unwrap();
+    assert_eq!(signature_from_abi(&entry), "approve(address,uint256)");
+    assert_eq!(hex::encode(selector_for(&entry).unwrap()), "095ea7b3");
+    assert_eq!(resolved_types(&entry), [("address payable".to_string(), "address".to_string())]);
+}
+
+#[test]
+fn contracts_and_enums_are_resolved() {
+    let json = r#"{ "type": "function", "name": "f", "inputs": [
+        { "name": "token", "type": "contract IERC20" },
+        { "name": "pools", "type": "IPool[]", "internalType": "contract IPool[]" },
+        { "name": "status", "type": "Status", "internalType": "enum Vault.Status" },
+        { "name": "order", "type": "Order", "internalType": "struct Vault.Order" }
+    ] }"#;
+    let entry: AbiEntry = serde_json::from_str(json).unwrap();
+    assert_eq!(signature_from_abi(&entry), "f(address,address[],uint8,Order)");
+    assert_eq!(invalid_types(&entry), ["Order"]);
+}
@@ -0,0 +1,62 @@
+//! Anonymous events have no topic0 in their logs, so no topic is listed for them.
+
+use std::process::Command;
+
+/// Run the binary on the fixture ABI, printing the tables to the standard output.
+fn run(args: &[&str]) -> String {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/anonymous.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["--abi", fixture, "-", "-q"])
+        .args(args)
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Lines of a `# <kind>` section of the standard output.
+fn section<'a>(stdout: &'a str, kind: &str) -> Vec<&'a str> {
+    let title = format!("# {}", kind);
+    stdout.lines().skip_while(|line| *line != title).skip(1).take_while(|line| !line.starts_with("# ")).collect()
+}
+
+#[test]
+fn anonymous_event_has_an_empty_topic() {
+    let stdout = run(&[]);
+    assert_eq!(
+        section(&stdout, "events"),
+        [
+            "contractName,event,topic,indexed",
+            "anonymous,,,",
+            ",\"Burned(address,uint256) [anonymous]\",,0",
+            ",\"Minted(address,uint256)\",0x30385c845b448a36257a6a1716e6ad2e1bc2cbe333cde1e69fe849ad6511adfe,0",
+        ]
+    );
+}
+
+#[test]
+fn anonymous_event_is_left_out_of_the_topics() {
+    let stdout = run(&["--format", "topics-only"]);
+    assert_eq!(
+        section(&stdout, "topics"),
+        [
+            "topic,signature",
+            "0x30385c845b448a36257a6a1716e6ad2e1bc2cbe333cde1e69fe849ad6511adfe,\"Minted(address,uint256)\"",
+        ]
+    );
+}
+
+#[test]
+fn anonymous_event_indexed_parameters_start_at_topic0() {
+    let stdout = run(&["--topics-layout", "--only", "events"]);
+    let minted = "0x30385c845b448a36257a6a1716e6ad2e1bc2cbe333cde1e69fe849ad6511adfe";
+    assert_eq!(
+        section(&stdout, "events"),
+        [
+            "contractName,event,topic,indexed,topic0,topic1,topic2,topic3".to_string(),
+            "anonymous,,,,,,,".to_string(),
+            ",\"Burned(address,uint256) [anonymous]\",,0,address,,,".to_string(),
+            format!(",\"Minted(address,uint256)\",{},0,{},address,,", minted, minted),
+        ]
+    );
+}
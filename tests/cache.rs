@@ -0,0 +1,49 @@
+//! ABIs reused from `.selector_cache.json` (`--cache`) until the file or one of its imports changes.
+
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+/// Write the artifact of `contract` in `src/<contract>.sol`, with the given functions.
+fn write_artifact(root: &Path, contract: &str, functions: &[&str]) {
+    let abi: Vec<String> =
+        functions.iter().map(|name| format!(r#"{{ "type": "function", "name": "{}", "inputs": [] }}"#, name)).collect();
+    std::fs::create_dir_all(root.join(format!("out/{}.sol", contract))).unwrap();
+    let artifact = format!(r#"{{ "abi": [{}] }}"#, abi.join(","));
+    std::fs::write(root.join(format!("out/{0}.sol/{0}.json", contract)), artifact).unwrap();
+}
+
+/// `signature,selector` of `Token`'s functions printed by a cached run.
+fn token_functions(root: &Path) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .args(["src", "-", "-q", "--no-compile", "--cache"])
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().skip_while(|line| !line.starts_with("Token,")).skip(1);
+    let rows = lines.take_while(|line| line.starts_with(','));
+    rows.map(|row| row.split(',').skip(1).take(2).collect::<Vec<_>>().join(",")).collect()
+}
+
+#[test]
+fn changed_import_invalidates_the_cache() {
+    let root = common::temp_path("cache");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/Base.sol"), "contract Base {}\n").unwrap();
+    let token = "// Inherits its functions\nimport {Base} from \"./Base.sol\";\n\ncontract Token is Base {}\n";
+    std::fs::write(root.join("src/Token.sol"), token).unwrap();
+    write_artifact(&root, "Base", &[]);
+    write_artifact(&root, "Token", &["mint"]);
+    assert_eq!(token_functions(&root), ["mint(),0x1249c58b"]);
+
+    // Token.sol itself is unchanged
+    std::fs::write(root.join("src/Base.sol"), "contract Base {\n    function burn() external {}\n}\n").unwrap();
+    write_artifact(&root, "Base", &["burn"]);
+    write_artifact(&root, "Token", &["burn", "mint"]);
+    let functions = token_functions(&root);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(functions, ["burn(),0x44df8e70", "mint(),0x1249c58b"]);
+}
@@ -0,0 +1,35 @@
+//! `--format cast` lines, one per signature whatever the number of contracts declaring it.
+
+use std::process::Command;
+
+mod common;
+
+#[test]
+fn signature_hash_lines() {
+    let abi_path = common::temp_path("cast.json");
+    let abi = r#"[
+        { "type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": [
+            { "name": "to", "type": "address" },
+            { "name": "amount", "type": "uint256" }
+        ] },
+        { "type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+            { "name": "from", "type": "address", "indexed": true },
+            { "name": "to", "type": "address", "indexed": true },
+            { "name": "value", "type": "uint256", "indexed": false }
+        ] },
+        { "type": "event", "name": "Hidden", "anonymous": true, "inputs": [] }
+    ]"#;
+    std::fs::write(&abi_path, abi).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--format", "cast", "--stdout"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "transfer(address,uint256) 0xa9059cbb\n\
+         Transfer(address,address,uint256) 0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef\n"
+    );
+}
@@ -0,0 +1,68 @@
+//! Only the contract files changed since a git commit, with `--since`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+/// Forge project in a temporary folder with the compiled artifacts of `src/Token.sol` and `src/Vault.sol`.
+fn project(name: &str) -> PathBuf {
+    let root = common::temp_path(name);
+    let _ = std::fs::remove_dir_all(&root);
+    for (contract, function) in [("Token", "mint"), ("Vault", "deposit")] {
+        std::fs::create_dir_all(root.join(format!("out/{}.sol", contract))).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(format!("src/{}.sol", contract)), format!("contract {} {{}}\n", contract)).unwrap();
+        let abi = format!(r#"{{ "abi": [{{ "type": "function", "name": "{}", "inputs": [] }}] }}"#, function);
+        std::fs::write(root.join(format!("out/{0}.sol/{0}.json", contract)), abi).unwrap();
+    }
+    std::fs::write(root.join("foundry.toml"), "[profile.default]\n").unwrap();
+    root
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(root)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("git runs");
+    assert!(status.success());
+}
+
+fn run(root: &Path) -> std::process::Output {
+    // Not looking for a repository above the temporary folder
+    Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .env("GIT_CEILING_DIRECTORIES", root.parent().unwrap())
+        .args(["--no-compile", "--only", "functions", "src", "-", "--since", "HEAD"])
+        .output()
+        .expect("the binary runs")
+}
+
+#[test]
+fn only_changed_files_are_processed() {
+    let root = project("since");
+    git(&root, &["init", "-q"]);
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "Contracts"]);
+    std::fs::write(root.join("src/Vault.sol"), "contract Vault { function deposit() external {} }\n").unwrap();
+    let output = run(&root);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "# selectors\ncontractName,function,selector,stateMutability,payable\nVault,,,,\n,deposit(),0xd0e30db0,,false\n"
+    );
+}
+
+#[test]
+fn every_file_outside_of_a_repository() {
+    let root = project("since_no_repo");
+    let output = run(&root);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Token,") && stdout.contains("Vault,"), "{}", stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("isn't in a git repository"));
+}
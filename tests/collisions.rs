@@ -0,0 +1,82 @@
+//! Function signatures of the run sharing the same selector.
+#![cfg(unix)]
+
+mod common;
+
+#[test]
+fn collisions_csv_and_exit_code() {
+    let abi = include_str!("fixtures/collision.json");
+    for (args, fails) in [(&[][..], false), (&["--fail-on-collision"][..], true)] {
+        let root = common::forge_project("collisions", &[("Burner", abi)]);
+        let output = common::run_in_project(&root, &[&["src", "generated"][..], args].concat());
+        let collisions = std::fs::read_to_string(root.join("generated/collisions.csv"));
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(output.status.success(), !fails, "{}", String::from_utf8_lossy(&output.stderr));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("0x42966c68: 'burn(uint256)' and 'collate_propagate_storage(bytes16)'"), "{}", stderr);
+        assert_eq!(
+            collisions.unwrap(),
+            "selector,signatureA,signatureB\n0x42966c68,burn(uint256),collate_propagate_storage(bytes16)\n"
+        );
+    }
+}
+
+#[test]
+fn collisions_use_the_hash_function_of_the_run() {
+    let root = common::forge_project("collisions_sha256", &[("Burner", include_str!("fixtures/collision.json"))]);
+    let output = common::run_in_project(&root, &["src", "-", "-q", "--fail-on-collision", "--hash", "sha256"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(",burn(uint256),0x308dce5f,"), "{}", stdout);
+    assert!(stdout.contains(",collate_propagate_storage(bytes16),0x35455673,"), "{}", stdout);
+}
+
+#[test]
+fn proxy_clashes_csv() {
+    let contracts = [
+        ("Proxy", include_str!("fixtures/proxy/Proxy.json")),
+        ("Implementation", include_str!("fixtures/proxy/Implementation.json")),
+    ];
+    let root = common::forge_project("proxy", &contracts);
+    let output = common::run_in_project(&root, &["src", "generated", "--proxy", "Proxy", "--impl", "Implementation"]);
+    let clashes = std::fs::read_to_string(root.join("generated/proxy_clashes.csv"));
+    std::fs::remove_dir_all(&root).unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Proxy selector clash 0x3659cfe6: 'upgradeTo(address)' in 'Proxy'"), "{}", stderr);
+    assert_eq!(
+        clashes.unwrap(),
+        "selector,proxySignature,implementationSignature\n0x3659cfe6,upgradeTo(address),upgradeTo(address)\n"
+    );
+}
+
+/// With the selectors in a database elsewhere, the output folder is only created for the `report` file.
+fn report_beside_a_database(contracts: &[(&str, &str)], args: &[&str], report: &str) -> String {
+    let root = common::forge_project("reports", contracts);
+    let run_args = [&["src", "generated", "-q", "--format", "sqlite", "--db", "selectors.db"][..], args].concat();
+    let output = common::run_in_project(&root, &run_args);
+    let report = std::fs::read_to_string(root.join("generated").join(report));
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    report.unwrap()
+}
+
+#[test]
+fn collisions_csv_creates_the_output_folder() {
+    assert_eq!(
+        report_beside_a_database(&[("Burner", include_str!("fixtures/collision.json"))], &[], "collisions.csv"),
+        "selector,signatureA,signatureB\n0x42966c68,burn(uint256),collate_propagate_storage(bytes16)\n"
+    );
+}
+
+#[test]
+fn proxy_clashes_csv_creates_the_output_folder() {
+    let contracts = [
+        ("Proxy", include_str!("fixtures/proxy/Proxy.json")),
+        ("Implementation", include_str!("fixtures/proxy/Implementation.json")),
+    ];
+    assert_eq!(
+        report_beside_a_database(&contracts, &["--proxy", "Proxy", "--impl", "Implementation"], "proxy_clashes.csv"),
+        "selector,proxySignature,implementationSignature\n0x3659cfe6,upgradeTo(address),upgradeTo(address)\n"
+    );
+}
@@ -0,0 +1,67 @@
+//! Columns of selectors.csv picked and ordered with `--columns`.
+
+use std::process::Command;
+
+mod common;
+
+fn run(abi: &str, args: &[&str]) -> std::process::Output {
+    let abi_path = common::temp_path("columns.json");
+    std::fs::write(&abi_path, abi).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "Token", "--stdout", "--only", "functions"])
+        .args(args)
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    output
+}
+
+const ABI: &str = r#"[
+    { "type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": [
+        { "name": "to", "type": "address" },
+        { "name": "amount", "type": "uint256" }
+    ] },
+    { "type": "function", "name": "totalSupply", "stateMutability": "view", "inputs": [] }
+]"#;
+
+#[test]
+fn columns_in_the_given_order() {
+    let output = run(ABI, &["--columns", "contract,selector,signature,mutability"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "# selectors\n\
+         contractName,selector,function,stateMutability\n\
+         Token,0x18160ddd,totalSupply(),view\n\
+         Token,0xa9059cbb,\"transfer(address,uint256)\",nonpayable\n"
+    );
+}
+
+#[test]
+fn unknown_and_missing_columns_are_rejected() {
+    let output = run(ABI, &["--columns", "selector,gas"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown column 'gas'"));
+
+    let output = run(ABI, &["--columns", "selector,hash"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Column 'hash' needs '--full-hash'"));
+}
+
+#[test]
+fn bytecode_functions_are_kept() {
+    let bytecode = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dispatcher.hex");
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--from-bytecode", bytecode, "--stdout", "--only", "functions"])
+        .args(["--columns", "contract,selector,signature"])
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "# selectors\n\
+         contractName,selector,function\n\
+         dispatcher,0xa9059cbb,\n\
+         dispatcher,0xdd62ed3e,\n"
+    );
+}
@@ -0,0 +1,61 @@
+//! Helpers shared by the tests running the binary.
+// Each test file only uses some of them
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_PATH: AtomicUsize = AtomicUsize::new(0);
+
+/// Path in the temporary folder for a test's ABI or project, e.g. `selector_calculator_<pid>_<n>_<name>`.
+/// Unique to each call: the tests of a file run in parallel in the same process.
+pub fn temp_path(name: &str) -> PathBuf {
+    let n = NEXT_PATH.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("selector_calculator_{}_{}_{}", std::process::id(), n, name))
+}
+
+/// `forge` of the test projects: `compile` has nothing to do, the artifacts being written already, and
+/// `inspect <file>:<Name> abi` prints `abi/<Name>.json`.
+const FAKE_FORGE: &str = r#"#!/bin/sh
+if [ "$1" = inspect ]; then
+    cat "abi/${2##*:}.json"
+fi
+"#;
+
+/// A forge project in a temporary folder with a `src/<Name>.sol` file per `(Name, ABI)` contract, its artifact
+/// and a fake `forge` in `bin/` (see `run_in_project`).
+#[cfg(unix)]
+pub fn forge_project(name: &str, contracts: &[(&str, &str)]) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = temp_path(name);
+    for folder in ["src", "abi", "bin"] {
+        std::fs::create_dir_all(root.join(folder)).unwrap();
+    }
+    std::fs::write(root.join("foundry.toml"), "[profile.default]\n").unwrap();
+    for (contract, abi) in contracts {
+        std::fs::write(root.join(format!("src/{}.sol", contract)), format!("contract {} {{}}\n", contract)).unwrap();
+        std::fs::write(root.join(format!("abi/{}.json", contract)), abi).unwrap();
+        std::fs::create_dir_all(root.join(format!("out/{}.sol", contract))).unwrap();
+        std::fs::write(root.join(format!("out/{0}.sol/{0}.json", contract)), format!(r#"{{ "abi": {} }}"#, abi))
+            .unwrap();
+    }
+    let forge = root.join("bin/forge");
+    std::fs::write(&forge, FAKE_FORGE).unwrap();
+    std::fs::set_permissions(&forge, std::fs::Permissions::from_mode(0o755)).unwrap();
+    root
+}
+
+/// Run the binary in a `forge_project`, with its fake `forge` first in the `PATH`.
+pub fn run_in_project(root: &Path, args: &[&str]) -> Output {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let mut paths = vec![root.join("bin")];
+    paths.extend(std::env::split_paths(&path));
+    Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .env("PATH", std::env::join_paths(paths).unwrap())
+        .args(args)
+        .output()
+        .expect("the binary runs")
+}
@@ -0,0 +1,31 @@
+//! Warnings and errors read from the output of `forge compile`.
+
+use selector_calculator::compile_diagnostics;
+
+#[test]
+fn warnings_and_errors_with_their_location() {
+    let output = "Compiler run successful with warnings:
+Warning (2072): Unused local variable.
+  --> src/Token.sol:10:9:
+   |
+10 |         uint256 unused = 1;
+Error: Compiler run failed:
+TypeError (9574): Type uint256 is not implicitly convertible to expected type address.
+  --> src/Broken.sol:5:9:
+Warning: This is a pre-release compiler version.";
+    let diagnostics = compile_diagnostics(output);
+    let summary: Vec<(bool, &str, Option<&str>)> =
+        diagnostics.iter().map(|d| (d.is_error, d.message.as_str(), d.location.as_deref())).collect();
+    assert_eq!(
+        summary,
+        [
+            (false, "Warning (2072): Unused local variable.", Some("src/Token.sol:10:9")),
+            (
+                true,
+                "TypeError (9574): Type uint256 is not implicitly convertible to expected type address.",
+                Some("src/Broken.sol:5:9")
+            ),
+            (false, "Warning: This is a pre-release compiler version.", None),
+        ]
+    );
+}
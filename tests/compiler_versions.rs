@@ -0,0 +1,20 @@
+//! Solc versions read from the output of `forge compile`.
+
+use selector_calculator::compiler_versions;
+
+#[test]
+fn versions_of_each_compile_line() {
+    let output = "[⠊] Compiling...
+[⠒] Compiling 12 files with Solc 0.8.24
+[⠑] Compiling 2 files with Solc 0.7.6
+[⠘] Solc 0.8.24 finished in 1.21s
+[⠃] Compiling 1 files with Vyper 0.3.10
+Compiler run successful!";
+    assert_eq!(compiler_versions(output), ["0.7.6", "0.8.24"]);
+}
+
+#[test]
+fn older_forge_and_skipped_compilation() {
+    assert_eq!(compiler_versions("Compiling 3 files with 0.8.19\nCompiler run successful"), ["0.8.19"]);
+    assert!(compiler_versions("No files changed, compilation skipped").is_empty());
+}
@@ -0,0 +1,50 @@
+//! CSV fields separated by another character than `,` with `--delimiter`.
+
+use std::process::Command;
+
+mod common;
+
+fn run(args: &[&str]) -> std::process::Output {
+    let abi = r#"[
+        { "type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": [
+            { "name": "to", "type": "address" },
+            { "name": "amount", "type": "uint256" }
+        ] },
+        { "type": "function", "name": "odd;name", "stateMutability": "view", "inputs": [] }
+    ]"#;
+    let abi_path = common::temp_path("delimiter.json");
+    std::fs::write(&abi_path, abi).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "Token", "--stdout", "--only", "functions"])
+        .args(args)
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    output
+}
+
+#[test]
+fn fields_with_the_delimiter_are_quoted() {
+    let output = run(&["--delimiter", ";"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("contractName;function;selector;stateMutability;payable\n"), "{}", stdout);
+    // Comas don't need quotes anymore, semicolons do
+    assert!(stdout.contains(";transfer(address,uint256);0xa9059cbb;nonpayable;false\n"), "{}", stdout);
+    assert!(stdout.contains(";\"odd;name()\";"), "{}", stdout);
+}
+
+#[test]
+fn backslash_t_is_a_tab() {
+    let output = run(&["--delimiter", "\\t"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("contractName\tfunction\tselector\t"));
+}
+
+#[test]
+fn delimiter_is_a_single_character() {
+    for delimiter in [";;", "", "\""] {
+        let output = run(&["--delimiter", delimiter]);
+        assert!(!output.status.success(), "'{}' should be rejected", delimiter);
+    }
+}
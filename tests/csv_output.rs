@@ -0,0 +1,34 @@
+//! CSV tables read back with a CSV parser, the tuples' signatures having comas.
+
+use std::process::Command;
+
+mod common;
+
+#[test]
+fn signatures_with_comas_are_quoted() {
+    let abi_path = common::temp_path("csv.json");
+    let abi = r#"[{ "type": "function", "name": "fill", "stateMutability": "payable", "inputs": [
+        { "name": "order", "type": "tuple", "components": [
+            { "name": "maker", "type": "address" },
+            { "name": "amounts", "type": "uint256[]" }
+        ] },
+        { "name": "data", "type": "bytes" }
+    ] }]"#;
+    std::fs::write(&abi_path, abi).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "Exchange", "--with-names", "--stdout"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let selectors = stdout.split("# selectors\n").nth(1).unwrap().split("\n# ").next().unwrap();
+    let mut reader = csv::Reader::from_reader(selectors.as_bytes());
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(&rows[0][0], "Exchange");
+    assert_eq!(&rows[1][1], "fill((address,uint256[]),bytes)");
+    assert_eq!(&rows[1][4], "order,data");
+    assert_eq!(&rows[1][5], "true");
+}
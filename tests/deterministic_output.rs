@@ -0,0 +1,72 @@
+//! Two runs on the same contracts write byte-identical files, whatever the order the files are walked in.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+const CONTRACTS: [(&str, &str); 3] = [("Vault", "withdraw"), ("Token", "mint"), ("Auction", "bid")];
+
+/// A forge project with the compiled artifacts of `CONTRACTS`, its files created in the given order.
+fn project(contracts: impl Iterator<Item = (&'static str, &'static str)>) -> PathBuf {
+    let root = common::temp_path("deterministic");
+    for (contract, function) in contracts {
+        std::fs::create_dir_all(root.join(format!("out/{}.sol", contract))).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(format!("src/{}.sol", contract)), format!("contract {} {{}}\n", contract)).unwrap();
+        let abi = format!(
+            r#"{{ "abi": [
+                {{ "type": "function", "name": "{0}", "inputs": [{{ "name": "amount", "type": "uint256" }}] }},
+                {{ "type": "function", "name": "{0}", "inputs": [] }},
+                {{ "type": "event", "name": "{1}", "anonymous": false, "inputs": [] }}
+            ] }}"#,
+            function, contract
+        );
+        std::fs::write(root.join(format!("out/{0}.sol/{0}.json", contract)), abi).unwrap();
+    }
+    root
+}
+
+/// Content of every file written in the output folder, by path relative to it.
+fn run(root: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .args(["src", "generated", "-q", "--no-compile"])
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let generated = root.join("generated");
+    let files = walk(&generated).into_iter().map(|path| (path.strip_prefix(&generated).unwrap().to_path_buf(), path));
+    files.map(|(relative, path)| (relative, std::fs::read(path).unwrap())).collect()
+}
+
+fn walk(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(walk(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+#[test]
+fn runs_write_the_same_bytes() {
+    let (first, second) = (project(CONTRACTS.into_iter()), project(CONTRACTS.into_iter().rev()));
+    let (first_files, again_files, second_files) = (run(&first), run(&first), run(&second));
+    std::fs::remove_dir_all(&first).unwrap();
+    std::fs::remove_dir_all(&second).unwrap();
+    assert!(first_files.contains_key(Path::new("selectors/selectors.csv")), "{:?}", first_files.keys());
+    assert!(first_files == again_files);
+    assert!(first_files == second_files);
+    let selectors = String::from_utf8(first_files[Path::new("selectors/selectors.csv")].clone()).unwrap();
+    let names = selectors.lines().skip(1).filter(|line| !line.starts_with(','));
+    let contracts: Vec<&str> = names.map(|line| line.split(',').next().unwrap()).collect();
+    assert_eq!(contracts, ["Auction", "Token", "Vault"]);
+    // Sorted by signature, not in the ABI's order
+    assert!(selectors.find(",bid(),").unwrap() < selectors.find(",bid(uint256),").unwrap(), "{}", selectors);
+}
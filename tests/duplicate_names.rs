@@ -0,0 +1,71 @@
+//! Contracts with the same name in different files are told apart by their folder.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+/// A forge project in a temporary folder with a `(source, artifacts' folder, contract, function)` per contract,
+/// so it can be run with `--no-compile`.
+fn project(name: &str, contracts: &[(&str, &str, &str, &str)]) -> PathBuf {
+    let root = common::temp_path(name);
+    let _ = std::fs::remove_dir_all(&root);
+    for (source, artifacts, contract, function) in contracts {
+        let source_path = root.join(source);
+        std::fs::create_dir_all(source_path.parent().unwrap()).unwrap();
+        std::fs::write(&source_path, format!("contract {} {{}}\n", contract)).unwrap();
+        let artifacts = root.join("out").join(artifacts);
+        std::fs::create_dir_all(&artifacts).unwrap();
+        let abi = format!(r#"{{ "abi": [{{ "type": "function", "name": "{}", "inputs": [] }}] }}"#, function);
+        std::fs::write(artifacts.join(format!("{}.json", contract)), abi).unwrap();
+    }
+    root
+}
+
+/// Run the binary on the `src` folder, returning the printed selectors' table.
+fn selectors(root: &Path) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .args(["src", "-", "-q", "--no-compile", "--only", "functions"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_dir_all(root).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap().lines().skip(2).map(str::to_string).collect()
+}
+
+#[test]
+fn same_name_in_different_folders() {
+    // forge writes the 2nd 'Token.sol' artifacts under their parent folder
+    let root = project(
+        "duplicates",
+        &[
+            ("src/core/Token.sol", "Token.sol", "Token", "mint"),
+            ("src/mock/Token.sol", "mock/Token.sol", "Token", "burn"),
+            ("src/Vault.sol", "Vault.sol", "Vault", "deposit"),
+        ],
+    );
+    assert_eq!(
+        selectors(&root),
+        [
+            "Vault,,,,",
+            ",deposit(),0xd0e30db0,,false",
+            "core/Token,,,,",
+            ",mint(),0x1249c58b,,false",
+            "mock/Token,,,,",
+            ",burn(),0x44df8e70,,false",
+        ]
+    );
+}
+
+#[test]
+fn same_name_in_the_same_folder() {
+    let root = project(
+        "same_folder",
+        &[("src/A.sol", "A.sol", "Token", "mint"), ("src/B.sol", "B.sol", "Token", "burn")],
+    );
+    assert_eq!(
+        selectors(&root),
+        ["A.sol:Token,,,,", ",mint(),0x1249c58b,,false", "B.sol:Token,,,,", ",burn(),0x44df8e70,,false"]
+    );
+}
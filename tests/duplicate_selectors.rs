@@ -0,0 +1,45 @@
+//! Functions of the same contract sharing a selector, only found in malformed ABIs.
+
+use std::process::Command;
+
+mod common;
+
+/// `burn(uint256)` and `collate_propagate_storage(bytes16)` are both 0x42966c68.
+fn run(args: &[&str]) -> std::process::Output {
+    let abi = r#"[
+        { "type": "function", "name": "burn", "inputs": [{ "name": "amount", "type": "uint256" }] },
+        { "type": "function", "name": "collate_propagate_storage", "inputs": [{ "name": "", "type": "bytes16" }] }
+    ]"#;
+    let abi_path = common::temp_path("duplicates.json");
+    std::fs::write(&abi_path, abi).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["--abi", abi_path.to_str().unwrap(), "--name", "Token", "--stdout"])
+        .args(args)
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    output
+}
+
+#[test]
+fn duplicates_are_a_warning() {
+    let output = run(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "[WARN ] Contract 'Token' has two functions with the selector 0x42966c68: 'burn(uint256)' and \
+             'collate_propagate_storage(bytes16)'"
+        ),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn duplicates_are_an_error_with_strict() {
+    let output = run(&["--strict"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 selector(s) shared by different functions of the same contract"), "{}", stderr);
+}
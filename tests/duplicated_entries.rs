@@ -0,0 +1,46 @@
+//! The same function listed twice in an ABI (e.g. inherited and overridden) gives a single row.
+
+use std::process::Command;
+
+/// Rows of the `# selectors` section for the fixture ABI, where `transfer(address,uint256)` is listed twice
+/// with other parameters' names.
+fn function_rows(args: &[&str]) -> Vec<String> {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/duplicated.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["--abi", fixture, "-", "-q"])
+        .args(args)
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout
+        .lines()
+        .skip_while(|line| *line != "# selectors")
+        .skip(1)
+        .take_while(|line| !line.starts_with('#'))
+        .filter(|line| line.starts_with(','))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn duplicated_entry_is_listed_once() {
+    let rows = function_rows(&["--no-sort"]);
+    let expected = [
+        ",\"transfer(address,uint256)\",0xa9059cbb,nonpayable",
+        ",totalSupply(),0x18160ddd,view",
+        ",transfer(address),0x1a695230,nonpayable",
+    ];
+    assert_eq!(rows.len(), expected.len(), "{:?}", rows);
+    for (row, expected) in rows.iter().zip(expected) {
+        assert!(row.starts_with(expected), "{:?}", rows);
+    }
+}
+
+#[test]
+fn first_entry_is_kept() {
+    let rows = function_rows(&["--with-names"]);
+    assert_eq!(rows.len(), 3);
+    let first = ",\"transfer(address,uint256)\",0xa9059cbb,nonpayable,\"to,amount\"";
+    assert!(rows.iter().any(|row| row.starts_with(first)), "{:?}", rows);
+}
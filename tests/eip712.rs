@@ -0,0 +1,77 @@
+//! EIP-712 encoded types and type hashes of the ABIs' structs, checked against the EIP's example.
+
+use std::process::Command;
+
+use selector_calculator::{eip712_types, signature_hash, AbiEntry};
+
+mod common;
+
+/// `sign(Mail mail)` of the EIP's example, with `Mail(Person from,Person to,string contents)`.
+const MAIL_ABI: &str = r#"[{ "type": "function", "name": "sign", "inputs": [
+    { "name": "mail", "type": "tuple", "internalType": "struct Mailbox.Mail", "components": [
+        { "name": "from", "type": "tuple", "internalType": "struct Mailbox.Person", "components": [
+            { "name": "name", "type": "string" },
+            { "name": "wallet", "type": "address" }
+        ] },
+        { "name": "to", "type": "tuple", "internalType": "struct Mailbox.Person", "components": [
+            { "name": "name", "type": "string" },
+            { "name": "wallet", "type": "address" }
+        ] },
+        { "name": "contents", "type": "string" }
+    ] }
+] }]"#;
+
+#[test]
+fn referenced_structs_follow_the_primary_one() {
+    let abi: Vec<AbiEntry> = serde_json::from_str(MAIL_ABI).unwrap();
+    let types = eip712_types(&abi);
+    assert_eq!(
+        types,
+        [
+            (
+                "Mail".to_string(),
+                "Mail(Person from,Person to,string contents)Person(string name,address wallet)".to_string()
+            ),
+            ("Person".to_string(), "Person(string name,address wallet)".to_string()),
+        ]
+    );
+    assert_eq!(hex::encode(signature_hash(&types[0].1)), "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2");
+}
+
+#[test]
+fn tuples_without_a_struct_name_are_left_out() {
+    let json = r#"[{ "type": "function", "name": "f", "inputs": [
+        { "name": "order", "type": "tuple[]", "internalType": "struct Vault.Order[]", "components": [
+            { "name": "maker", "type": "address" },
+            { "name": "fee", "type": "tuple", "components": [{ "name": "amount", "type": "uint" }] }
+        ] },
+        { "name": "permit", "type": "tuple", "internalType": "struct Vault.Permit", "components": [
+            { "name": "owner", "type": "address" },
+            { "name": "value", "type": "uint" }
+        ] }
+    ] }]"#;
+    let abi: Vec<AbiEntry> = serde_json::from_str(json).unwrap();
+    assert_eq!(eip712_types(&abi), [("Permit".to_string(), "Permit(address owner,uint256 value)".to_string())]);
+}
+
+#[test]
+fn type_hashes_are_written() {
+    let abi_path = common::temp_path("typehash.json");
+    std::fs::write(&abi_path, MAIL_ABI).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "Mailbox", "--typehash", "--stdout"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(
+            "Mailbox,Mail,\"Mail(Person from,Person to,string contents)Person(string name,address wallet)\",\
+             0xa0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2\n"
+        ),
+        "{}",
+        stdout
+    );
+}
@@ -0,0 +1,31 @@
+//! Contracts without ABI entries (e.g. interface-only files) and unreadable artifacts don't stop the run.
+
+use std::process::{Command, Output};
+
+/// Run the binary on the fixture project, with `IToken.sol`'s empty ABI and `Broken.sol`'s invalid artifact.
+fn run() -> Output {
+    Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/interface_only"))
+        .args(["src", "-", "--no-compile"])
+        .output()
+        .expect("the binary runs")
+}
+
+#[test]
+fn empty_abi_has_no_name_row() {
+    let output = run();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.lines().any(|line| line.starts_with(",mint(),0x1249c58b,")), "{}", stdout);
+    assert!(!stdout.lines().any(|line| line.starts_with("IToken,")), "{}", stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Empty ABI for 'IToken', it's left out of the tables"));
+}
+
+#[test]
+fn invalid_artifact_is_a_failure_of_its_file() {
+    let output = run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error parsing artifact's JSON \"out/Broken.sol/Broken.json\""), "{}", stderr);
+    assert!(stderr.contains("1 contract(s) couldn't be processed"), "{}", stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+    assert!(!output.status.success());
+}
@@ -0,0 +1,248 @@
+//! Selectors and topics of the ERC-20 and ERC-721 interfaces, checked against their published values.
+
+use selector_calculator::{
+    event_layout, event_topic, function_selector, known_selector, selector_for, signature_from_abi, topic_for, AbiEntry,
+    KNOWN_SELECTORS,
+};
+
+const ERC20_ABI: &str = include_str!("fixtures/erc20.json");
+const ERC721_ABI: &str = include_str!("fixtures/erc721.json");
+
+fn parse(abi_json: &str) -> Vec<AbiEntry> {
+    serde_json::from_str(abi_json).expect("valid ABI fixture")
+}
+
+/// `(signature, selector)` of every function of the ABI, in the fixture's order.
+fn selectors(abi: &[AbiEntry]) -> Vec<(String, String)> {
+    abi.iter()
+        .filter(|entry| entry.kind == "function")
+        .map(|entry| {
+            let signature = signature_from_abi(entry);
+            let selector = format!("0x{}", hex::encode(function_selector(&signature)));
+            (signature, selector)
+        })
+        .collect()
+}
+
+/// `(signature, topic)` of every event of the ABI, in the fixture's order.
+fn topics(abi: &[AbiEntry]) -> Vec<(String, String)> {
+    abi.iter()
+        .filter(|entry| entry.kind == "event")
+        .map(|entry| {
+            let signature = signature_from_abi(entry);
+            let topic = format!("0x{}", hex::encode(event_topic(&signature)));
+            (signature, topic)
+        })
+        .collect()
+}
+
+fn expected(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(signature, hash)| (signature.to_string(), hash.to_string())).collect()
+}
+
+/// ERC-165 interface id: the XOR of the given selectors.
+fn interface_id(selectors: &[(String, String)], signatures: &[&str]) -> String {
+    let mut id = [0u8; 4];
+    for (_, selector) in selectors.iter().filter(|(signature, _)| signatures.contains(&signature.as_str())) {
+        let bytes = hex::decode(&selector[2..]).unwrap();
+        for (byte, other) in id.iter_mut().zip(bytes) {
+            *byte ^= other;
+        }
+    }
+    format!("0x{}", hex::encode(id))
+}
+
+#[test]
+fn erc20_function_selectors() {
+    let abi = parse(ERC20_ABI);
+    assert_eq!(
+        selectors(&abi),
+        expected(&[
+            ("allowance(address,address)", "0xdd62ed3e"),
+            ("approve(address,uint256)", "0x095ea7b3"),
+            ("balanceOf(address)", "0x70a08231"),
+            ("decimals()", "0x313ce567"),
+            ("name()", "0x06fdde03"),
+            ("symbol()", "0x95d89b41"),
+            ("totalSupply()", "0x18160ddd"),
+            ("transfer(address,uint256)", "0xa9059cbb"),
+            ("transferFrom(address,address,uint256)", "0x23b872dd"),
+        ])
+    );
+}
+
+#[test]
+fn erc20_event_topics() {
+    let abi = parse(ERC20_ABI);
+    assert_eq!(
+        topics(&abi),
+        expected(&[
+            (
+                "Approval(address,address,uint256)",
+                "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925",
+            ),
+            (
+                "Transfer(address,address,uint256)",
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            ),
+        ])
+    );
+}
+
+#[test]
+fn erc20_interface_id() {
+    let abi = parse(ERC20_ABI);
+    let functions = [
+        "totalSupply()",
+        "balanceOf(address)",
+        "transfer(address,uint256)",
+        "transferFrom(address,address,uint256)",
+        "approve(address,uint256)",
+        "allowance(address,address)",
+    ];
+    assert_eq!(interface_id(&selectors(&abi), &functions), "0x36372b07");
+}
+
+#[test]
+fn erc721_function_selectors() {
+    let abi = parse(ERC721_ABI);
+    assert_eq!(
+        selectors(&abi),
+        expected(&[
+            ("approve(address,uint256)", "0x095ea7b3"),
+            ("balanceOf(address)", "0x70a08231"),
+            ("getApproved(uint256)", "0x081812fc"),
+            ("isApprovedForAll(address,address)", "0xe985e9c5"),
+            ("name()", "0x06fdde03"),
+            ("ownerOf(uint256)", "0x6352211e"),
+            ("safeTransferFrom(address,address,uint256)", "0x42842e0e"),
+            ("safeTransferFrom(address,address,uint256,bytes)", "0xb88d4fde"),
+            ("setApprovalForAll(address,bool)", "0xa22cb465"),
+            ("supportsInterface(bytes4)", "0x01ffc9a7"),
+            ("symbol()", "0x95d89b41"),
+            ("tokenURI(uint256)", "0xc87b56dd"),
+            ("transferFrom(address,address,uint256)", "0x23b872dd"),
+        ])
+    );
+}
+
+#[test]
+fn erc721_event_topics() {
+    let abi = parse(ERC721_ABI);
+    assert_eq!(
+        topics(&abi),
+        expected(&[
+            (
+                "Approval(address,address,uint256)",
+                "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925",
+            ),
+            (
+                "ApprovalForAll(address,address,bool)",
+                "0x17307eab39ab6107e8899845ad3d59bd9653f200f220920489ca2b5937696c31",
+            ),
+            (
+                "Transfer(address,address,uint256)",
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            ),
+        ])
+    );
+}
+
+#[test]
+fn erc721_interface_ids() {
+    let abi = parse(ERC721_ABI);
+    let selectors = selectors(&abi);
+    let erc721 = [
+        "balanceOf(address)",
+        "ownerOf(uint256)",
+        "safeTransferFrom(address,address,uint256,bytes)",
+        "safeTransferFrom(address,address,uint256)",
+        "transferFrom(address,address,uint256)",
+        "approve(address,uint256)",
+        "setApprovalForAll(address,bool)",
+        "getApproved(uint256)",
+        "isApprovedForAll(address,address)",
+    ];
+    assert_eq!(interface_id(&selectors, &erc721), "0x80ac58cd");
+    let metadata = ["name()", "symbol()", "tokenURI(uint256)"];
+    assert_eq!(interface_id(&selectors, &metadata), "0x5b5e139f");
+    assert_eq!(interface_id(&selectors, &["supportsInterface(bytes4)"]), "0x01ffc9a7");
+}
+
+/// Enums and `address payable` only differ in the `internalType`, the signature uses the ABI `type`.
+#[test]
+fn internal_types_are_ignored() {
+    let abi = parse(
+        r#"[
+            {
+                "type": "function",
+                "name": "setStatus",
+                "inputs": [
+                    { "name": "status", "type": "uint8", "internalType": "enum Market.Status" },
+                    { "name": "to", "type": "address", "internalType": "address payable" },
+                    { "name": "data", "type": "bytes", "internalType": "bytes" },
+                    { "name": "uri", "type": "string", "internalType": "string" }
+                ],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            }
+        ]"#,
+    );
+    assert_eq!(selectors(&abi)[0].0, "setStatus(uint8,address,bytes,string)");
+}
+
+#[test]
+fn erc20_event_layouts() {
+    let abi = parse(ERC20_ABI);
+    let layouts: Vec<(String, Vec<String>, Vec<String>)> = abi
+        .iter()
+        .filter(|entry| entry.kind == "event")
+        .map(|entry| {
+            let (indexed, data) = event_layout(entry);
+            (signature_from_abi(entry), indexed, data)
+        })
+        .collect();
+    let types = |types: &[&str]| types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        layouts,
+        [
+            ("Approval(address,address,uint256)".to_string(), types(&["address", "address"]), types(&["uint256"])),
+            ("Transfer(address,address,uint256)".to_string(), types(&["address", "address"]), types(&["uint256"])),
+        ]
+    );
+}
+
+#[test]
+fn selectors_and_topics_of_entries() {
+    let abi = parse(ERC20_ABI);
+    let entry = |name: &str| abi.iter().find(|entry| entry.name.as_deref() == Some(name)).unwrap();
+    assert_eq!(selector_for(entry("transfer")).map(hex::encode).as_deref(), Some("a9059cbb"));
+    assert_eq!(topic_for(entry("transfer")), None);
+    assert_eq!(selector_for(entry("Transfer")), None);
+    assert_eq!(
+        topic_for(entry("Transfer")).map(hex::encode).as_deref(),
+        Some("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+    );
+
+    let others: Vec<AbiEntry> = serde_json::from_str(
+        r#"[
+            { "type": "error", "name": "InsufficientBalance", "inputs": [{ "name": "", "type": "uint256" }] },
+            { "type": "event", "name": "Anon", "anonymous": true, "inputs": [] },
+            { "type": "constructor", "inputs": [] },
+            { "type": "receive", "stateMutability": "payable" }
+        ]"#,
+    )
+    .unwrap();
+    let error_selector = function_selector("InsufficientBalance(uint256)");
+    assert_eq!(others.iter().map(selector_for).collect::<Vec<_>>(), [Some(error_selector), None, None, None]);
+    assert!(others.iter().all(|entry| topic_for(entry).is_none()));
+}
+
+#[test]
+fn known_selectors_are_their_signatures_ones() {
+    for known in KNOWN_SELECTORS.iter().filter(|known| !known.signature.is_empty()) {
+        assert_eq!(function_selector(known.signature), known.selector, "{}", known.signature);
+    }
+    assert_eq!(known_selector([0x01, 0xff, 0xc9, 0xa7]).map(|known| known.label), Some("ERC-165"));
+    assert!(known_selector([0xa9, 0x05, 0x9c, 0xbb]).is_none());
+}
@@ -0,0 +1,38 @@
+//! Contracts' names discovered from the source, and the file names they're written to.
+
+use selector_calculator::{declared_contracts, safe_file_name};
+
+#[test]
+fn declared_name_differs_from_file_stem() {
+    // As read from `My Token.sol`
+    let source = "// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Not a contract Fake in a comment
+interface IMyToken {}
+
+/* contract Commented {} */
+abstract contract MyTokenBase is IMyToken {}
+
+library Math {}
+
+contract MyToken is MyTokenBase {
+    bytes4 constant ID = type(IMyToken).interfaceId;
+}
+";
+    assert_eq!(declared_contracts(source), ["IMyToken", "MyTokenBase", "Math", "MyToken"]);
+}
+
+#[test]
+fn file_stem_with_a_space() {
+    assert_eq!(safe_file_name("My Token"), "My_Token");
+}
+
+#[test]
+fn unsafe_characters_are_replaced() {
+    assert_eq!(safe_file_name("Token-v2.1"), "Token-v2.1");
+    assert_eq!(safe_file_name("../Token"), ".._Token");
+    assert_eq!(safe_file_name("a/b\\c:d*e?f\"g<h>i|j"), "a_b_c_d_e_f_g_h_i_j");
+    assert_eq!(safe_file_name(".."), "__");
+    assert_eq!(safe_file_name(""), "_");
+}
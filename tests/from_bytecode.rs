@@ -0,0 +1,54 @@
+//! Functions of a runtime bytecode (`--from-bytecode`) have a selector but no signature.
+
+use std::process::{Command, Output};
+
+/// Run the binary on the fixture bytecode, dispatching `transfer(address,uint256)` and
+/// `allowance(address,address)`.
+fn run(args: &[&str]) -> Output {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dispatcher.hex");
+    Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["--from-bytecode", fixture, "-q"])
+        .args(args)
+        .output()
+        .expect("the binary runs")
+}
+
+/// Lines of a `# <kind>` section of the standard output.
+fn section(stdout: &str, kind: &str) -> Vec<String> {
+    let title = format!("# {}", kind);
+    let lines = stdout.lines().skip_while(|line| *line != title).skip(1);
+    lines.take_while(|line| !line.starts_with("# ")).map(str::to_string).collect()
+}
+
+/// Diff of the bytecode's functions with a `selectors.csv` fixture.
+fn diff(old: &str) -> Vec<String> {
+    let old = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), old);
+    let output = run(&["-", "--only", "functions", "--diff", &old]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    section(&String::from_utf8(output.stdout).unwrap(), "diff")
+}
+
+#[test]
+fn diff_matches_the_selectors() {
+    assert_eq!(
+        diff("erc20_selectors.csv"),
+        [
+            "change,contract,selector,signature,oldSignature",
+            "added,dispatcher,0xdd62ed3e,,",
+            "removed,dispatcher,0x095ea7b3,\"approve(address,uint256)\",",
+        ]
+    );
+}
+
+#[test]
+fn diff_with_a_previous_bytecode_run() {
+    assert_eq!(diff("dispatcher_selectors.csv"), ["change,contract,selector,signature,oldSignature"]);
+}
+
+#[test]
+fn sqlite_is_rejected() {
+    let output = run(&["--format", "sqlite", "--db", "never_written.db"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("The sqlite format can't be used with '--from-bytecode'"));
+    assert!(!std::path::Path::new("never_written.db").exists());
+}
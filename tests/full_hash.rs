@@ -0,0 +1,35 @@
+//! Full keccak256 hash of each function's signature (`--full-hash`), the selector being its 4 first bytes.
+
+use std::process::Command;
+
+use selector_calculator::{function_selector, signature_hash};
+
+#[test]
+fn selector_is_the_start_of_the_hash() {
+    // Both functions have the same selector, but not the same hash
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/collision.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["--abi", fixture, "-", "-q", "--full-hash"])
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines().skip_while(|line| *line != "# selectors").skip(1);
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let column = |name: &str| header.iter().position(|c| *c == name).unwrap();
+    let (signature, selector, hash) = (column("function"), column("selector"), column("hash"));
+    // The signatures have no comma to be quoted, leaving out the contract's name row
+    let rows: Vec<Vec<&str>> = lines
+        .take_while(|line| !line.starts_with('#'))
+        .map(|line| line.split(',').collect::<Vec<_>>())
+        .filter(|row| !row[signature].is_empty())
+        .collect();
+    assert_eq!(rows.len(), 2);
+    for row in rows {
+        assert_eq!(row[hash].len(), 66, "{:?}", row);
+        assert_eq!(row[hash][..10], *row[selector], "{:?}", row);
+        assert_eq!(row[hash], format!("0x{}", hex::encode(signature_hash(row[signature]))));
+    }
+    let hash = signature_hash("transfer(address,uint256)");
+    assert_eq!(hash[..4], function_selector("transfer(address,uint256)"));
+}
@@ -0,0 +1,79 @@
+//! Selectors and topics written without their `0x` prefix.
+
+use std::process::Command;
+
+mod common;
+
+#[test]
+fn hashes_without_prefix() {
+    let abi_path = common::temp_path("no_0x.json");
+    let abi = r#"[
+        { "type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": [
+            { "name": "to", "type": "address" },
+            { "name": "amount", "type": "uint256" }
+        ] },
+        { "type": "event", "name": "Paused", "anonymous": false, "inputs": [] },
+        { "type": "error", "name": "Unauthorized", "inputs": [] }
+    ]"#;
+    std::fs::write(&abi_path, abi).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "Token", "--no-0x", "--combined", "--stdout"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(",a9059cbb"), "{}", stdout);
+    assert!(stdout.contains(",9e87fac88ff661f02d44f95383c817fece4bce600a3dab7a54406878b965e752"), "{}", stdout);
+    assert!(stdout.contains(",82b42900"), "{}", stdout);
+    assert!(!stdout.contains("0x"), "{}", stdout);
+}
+
+const MINT: &str = r#"[{ "type": "function", "name": "mint", "stateMutability": "nonpayable", "inputs": [] }]"#;
+const BURN: &str = r#"[{ "type": "function", "name": "burn", "stateMutability": "nonpayable", "inputs": [] }]"#;
+
+/// `selectors.csv` written for the `old` ABI, then the `# diff` section of a run on the `new` one compared with it.
+fn diff_between_runs((old, old_args): (&str, &[&str]), (new, new_args): (&str, &[&str])) -> Vec<String> {
+    let abi_path = common::temp_path("diff_0x.json");
+    let output_dir = common::temp_path("diff_0x");
+    let run = |abi: &str, args: &[&str]| {
+        std::fs::write(&abi_path, abi).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+            .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "Token"])
+            .args(args)
+            .output()
+            .expect("the binary runs");
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+    run(old, &[&[output_dir.to_str().unwrap()][..], old_args].concat());
+    let old_csv = output_dir.join("selectors/selectors.csv");
+    let stdout = run(new, &[&["-", "--only", "functions", "--diff", old_csv.to_str().unwrap()][..], new_args].concat());
+    std::fs::remove_file(&abi_path).unwrap();
+    std::fs::remove_dir_all(&output_dir).unwrap();
+    stdout.lines().skip_while(|line| *line != "# diff").skip(1).map(str::to_string).collect()
+}
+
+#[test]
+fn diff_ignores_the_prefix() {
+    let unchanged = ["change,contract,selector,signature,oldSignature"];
+    assert_eq!(diff_between_runs((MINT, &["--no-0x"]), (MINT, &[])), unchanged);
+    assert_eq!(diff_between_runs((MINT, &[]), (MINT, &["--no-0x"])), unchanged);
+}
+
+#[test]
+fn diff_rows_follow_the_prefix_of_the_run() {
+    assert_eq!(
+        diff_between_runs((BURN, &["--no-0x"]), (MINT, &[])),
+        [
+            "change,contract,selector,signature,oldSignature",
+            "added,Token,0x1249c58b,mint(),",
+            "removed,Token,0x44df8e70,burn(),",
+        ]
+    );
+    assert_eq!(
+        diff_between_runs((BURN, &[]), (MINT, &["--no-0x"]))[1..],
+        ["added,Token,1249c58b,mint(),", "removed,Token,44df8e70,burn(),"]
+    );
+}
@@ -0,0 +1,70 @@
+//! Selectors and topics computed with another hash function or selectors' length.
+
+use selector_calculator::{
+    clear_hash_cache, event_topic, find_collisions, function_selector, HashAlgorithm, SelectorError, SelectorHasher,
+};
+
+const SHA256: SelectorHasher = SelectorHasher { algorithm: HashAlgorithm::Sha256, selector_bytes: 4 };
+
+#[test]
+fn default_is_the_evm_one() {
+    let hasher = SelectorHasher::default();
+    assert!(hasher.is_evm());
+    assert_eq!(hasher.selector("transfer(address,uint256)"), function_selector("transfer(address,uint256)"));
+    assert_eq!(hasher.topic("Transfer(address,address,uint256)"), event_topic("Transfer(address,address,uint256)"));
+}
+
+#[test]
+fn sha256_selectors_and_topics() {
+    assert!(!SHA256.is_evm());
+    assert_eq!(hex::encode(SHA256.selector("transfer(address,uint256)")), "3b88ef57");
+    assert_eq!(hex::encode(SHA256.selector("approve(address,uint256)")), "9f0bb8a9");
+    assert_eq!(
+        hex::encode(SHA256.topic("Transfer(address,address,uint256)")),
+        "2fb30cfca4728c7f62d6787ef949fc7943d813f5093ebc6c343c6cc6f3ec1a56"
+    );
+}
+
+#[test]
+fn selector_length() {
+    let hasher = SelectorHasher { selector_bytes: 8, ..SHA256 };
+    assert_eq!(hex::encode(hasher.selector("transfer(address,uint256)")), "3b88ef5774116344");
+    let keccak = SelectorHasher { selector_bytes: 2, ..SelectorHasher::default() };
+    assert_eq!(hex::encode(keccak.selector("transfer(address,uint256)")), "a905");
+    let full = SelectorHasher { selector_bytes: 32, ..SHA256 };
+    assert_eq!(full.selector("transfer(address,uint256)"), full.hash("transfer(address,uint256)"));
+}
+
+#[test]
+fn hash_names() {
+    assert_eq!(HashAlgorithm::from_name("keccak256"), Some(HashAlgorithm::Keccak256));
+    assert_eq!(HashAlgorithm::from_name("sha256"), Some(HashAlgorithm::Sha256));
+    assert_eq!(HashAlgorithm::from_name("sha3"), None);
+}
+
+#[test]
+fn selector_lengths() {
+    assert_eq!(SelectorHasher::new(HashAlgorithm::Sha256, 8), Ok(SelectorHasher { selector_bytes: 8, ..SHA256 }));
+    assert_eq!(SelectorHasher::new(HashAlgorithm::Keccak256, 0), Err(SelectorError::InvalidSelectorLength(0)));
+    assert_eq!(SelectorHasher::new(HashAlgorithm::Keccak256, 33), Err(SelectorError::InvalidSelectorLength(33)));
+}
+
+#[test]
+fn collisions_with_the_hasher() {
+    let keccak_pair = ["burn(uint256)", "collate_propagate_storage(bytes16)"];
+    assert_eq!(
+        find_collisions(keccak_pair, &SelectorHasher::default()),
+        [(vec![0x42, 0x96, 0x6c, 0x68], keccak_pair[0].to_string(), keccak_pair[1].to_string())]
+    );
+    assert!(find_collisions(keccak_pair, &SHA256).is_empty());
+    let one_byte = SelectorHasher { selector_bytes: 1, ..SHA256 };
+    assert_eq!(find_collisions(["f7()", "f19()"], &one_byte), [(vec![0x69], "f7()".to_string(), "f19()".to_string())]);
+}
+
+#[test]
+fn hashes_after_clearing_the_cache() {
+    let transfer = function_selector("transfer(address,uint256)");
+    clear_hash_cache();
+    assert_eq!(function_selector("transfer(address,uint256)"), transfer);
+    assert_eq!(SHA256.selector("burn(uint256)"), [0x30, 0x8d, 0xce, 0x5f]);
+}
@@ -0,0 +1,44 @@
+//! Positions of the indexed parameters of each event, in the `indexed` column.
+#![cfg(unix)]
+
+mod common;
+
+const TOKEN_ABI: &str = r#"[
+    { "type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+        { "name": "from", "type": "address", "indexed": true },
+        { "name": "to", "type": "address", "indexed": true },
+        { "name": "value", "type": "uint256", "indexed": false }
+    ] },
+    { "type": "event", "name": "Paused", "anonymous": false, "inputs": [
+        { "name": "account", "type": "address", "indexed": false }
+    ] }
+]"#;
+
+const NFT_ABI: &str = r#"[
+    { "type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+        { "name": "from", "type": "address", "indexed": true },
+        { "name": "to", "type": "address", "indexed": true },
+        { "name": "tokenId", "type": "uint256", "indexed": true }
+    ] }
+]"#;
+
+/// `events.csv` written for the ERC-20 like `Token` and the ERC-721 like `Nft`.
+fn events_csv() -> String {
+    let root = common::forge_project("indexed", &[("Token", TOKEN_ABI), ("Nft", NFT_ABI)]);
+    let output = common::run_in_project(&root, &["src", "generated"]);
+    let events = std::fs::read_to_string(root.join("generated/events/events.csv"));
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    events.unwrap()
+}
+
+#[test]
+fn erc20_and_erc721_transfers() {
+    let events = events_csv();
+    let transfer = ",\"Transfer(address,address,uint256)\",\
+                    0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+    assert!(events.contains(&format!("{},0;1\n", transfer)), "{}", events);
+    assert!(events.contains(&format!("{},0;1;2\n", transfer)), "{}", events);
+    // No indexed parameter
+    assert!(events.contains(",Paused(address),0x62e78cea01bee320cd4e420270b5ea74000d11b0c9f74754ebdbfc544b05a258,\n"));
+}
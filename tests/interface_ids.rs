@@ -0,0 +1,42 @@
+//! ERC-165 interface ID of each contract written with `--interface-id`.
+
+use std::process::Command;
+
+use selector_calculator::{function_selector, interface_id};
+
+mod common;
+
+#[test]
+fn xor_of_the_selectors() {
+    assert_eq!(interface_id([function_selector("supportsInterface(bytes4)")]), [0x01, 0xff, 0xc9, 0xa7]);
+    let metadata = ["name()", "symbol()", "tokenURI(uint256)"].map(function_selector);
+    assert_eq!(interface_id(metadata), [0x5b, 0x5e, 0x13, 0x9f]);
+    let erc20 = [
+        "totalSupply()",
+        "balanceOf(address)",
+        "transfer(address,uint256)",
+        "allowance(address,address)",
+        "approve(address,uint256)",
+        "transferFrom(address,address,uint256)",
+    ];
+    assert_eq!(interface_id(erc20.map(function_selector)), [0x36, 0x37, 0x2b, 0x07]);
+    assert_eq!(interface_id([]), [0; 4]);
+}
+
+#[test]
+fn erc721_interface_id() {
+    // IERC721 alone: the ERC-721 fixture without the metadata and ERC-165 functions
+    let mut abi: Vec<serde_json::Value> = serde_json::from_str(include_str!("fixtures/erc721.json")).unwrap();
+    abi.retain(|entry| !["name", "symbol", "tokenURI", "supportsInterface"].contains(&entry["name"].as_str().unwrap()));
+    let abi_path = common::temp_path("interface_id.json");
+    std::fs::write(&abi_path, serde_json::to_string(&abi).unwrap()).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "IERC721", "--interface-id", "--stdout"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_file(&abi_path).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.ends_with("# interface_ids\ncontractName,interfaceId,functions\nIERC721,0x80ac58cd,9\n"), "{}", stdout);
+}
@@ -0,0 +1,39 @@
+//! JSON Lines files, sorted whatever the order of the ABI.
+
+use std::process::Command;
+
+mod common;
+
+#[test]
+fn lines_and_keys_are_sorted() {
+    let dir = common::temp_path("jsonl");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let abi_path = dir.join("Token.json");
+    let abi = r#"[
+        { "type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": [
+            { "name": "to", "type": "address" },
+            { "name": "amount", "type": "uint256" }
+        ] },
+        { "type": "function", "name": "approve", "stateMutability": "nonpayable", "inputs": [
+            { "name": "spender", "type": "address" },
+            { "name": "amount", "type": "uint256" }
+        ] }
+    ]"#;
+    std::fs::write(&abi_path, abi).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .args(["-q", "--abi", abi_path.to_str().unwrap(), "--name", "Token", "--no-sort", "--only", "functions"])
+        .args(["--format", "jsonl", dir.join("out").to_str().unwrap()])
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let selectors = std::fs::read_to_string(dir.join("out/selectors/selectors.jsonl")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        selectors,
+        r#"{"contract":"Token","payable":false,"selector":"0x095ea7b3","signature":"approve(address,uint256)","stateMutability":"nonpayable"}
+{"contract":"Token","payable":false,"selector":"0xa9059cbb","signature":"transfer(address,uint256)","stateMutability":"nonpayable"}
+"#
+    );
+}
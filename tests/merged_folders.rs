@@ -0,0 +1,60 @@
+//! Several contracts' folders, each in its own forge project, merged in a single run.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+/// Forge projects `core` and `periphery` in a temporary folder, each with the compiled artifact of its
+/// `src/Token.sol` defining `function`.
+fn workspace(name: &str) -> PathBuf {
+    let root = common::temp_path(name);
+    let _ = std::fs::remove_dir_all(&root);
+    for (project, function) in [("core", "mint"), ("periphery", "swap")] {
+        let project = root.join(project);
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::create_dir_all(project.join("out/Token.sol")).unwrap();
+        std::fs::write(project.join("foundry.toml"), "[profile.default]\n").unwrap();
+        std::fs::write(project.join("src/Token.sol"), "contract Token {}\n").unwrap();
+        let abi = format!(r#"{{ "abi": [{{ "type": "function", "name": "{}", "inputs": [] }}] }}"#, function);
+        std::fs::write(project.join("out/Token.sol/Token.json"), abi).unwrap();
+    }
+    root
+}
+
+fn selectors(root: &Path, args: &[&str]) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .args(["-q", "--no-compile", "--only", "functions"])
+        .args(args)
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout.lines().skip(1).map(str::to_string).collect()
+}
+
+#[test]
+fn folders_are_merged_with_their_label() {
+    let root = workspace("merged");
+    let rows = selectors(&root, &["core/src", "periphery/src", "--output", "-"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(
+        rows,
+        [
+            "contractName,function,selector,stateMutability,repo,payable",
+            "core/src/Token,,,,,",
+            ",mint(),0x1249c58b,,core/src,false",
+            "periphery/src/Token,,,,,",
+            ",swap(),0x8119c065,,periphery/src,false",
+        ]
+    );
+}
+
+#[test]
+fn single_folder_has_no_label() {
+    let root = workspace("single");
+    let rows = selectors(&root.join("core"), &["src", "--output", "-"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(rows, ["contractName,function,selector,stateMutability,payable", "Token,,,,", ",mint(),0x1249c58b,,false"]);
+}
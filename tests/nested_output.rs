@@ -0,0 +1,56 @@
+//! An output folder inside the contracts' folder isn't read back by the next runs.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+mod common;
+
+/// A forge project in a temporary folder, with the compiled artifact of `src/Token.sol`.
+fn project(name: &str) -> PathBuf {
+    let root = common::temp_path(name);
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/Token.sol"), "contract Token {}\n").unwrap();
+    std::fs::create_dir_all(root.join("out/Token.sol")).unwrap();
+    let abi = r#"{ "abi": [{ "type": "function", "name": "mint", "inputs": [] }] }"#;
+    std::fs::write(root.join("out/Token.sol/Token.json"), abi).unwrap();
+    root
+}
+
+fn run(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .args(["-q", "--no-compile"])
+        .args(args)
+        .output()
+        .expect("the binary runs")
+}
+
+#[test]
+fn generated_files_are_not_rescanned() {
+    let root = project("nested");
+    // The interfaces are .sol files written under 'generated/', inside the walked folder
+    for _ in 0..2 {
+        let output = run(&root, &[".", "generated", "--emit-interface"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+    let interface = root.join("generated/interfaces/InterfaceToken.sol");
+    let selectors = std::fs::read_to_string(root.join("generated/selectors/selectors.csv")).unwrap();
+    let interface_exists = interface.is_file();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(interface_exists);
+    assert_eq!(
+        selectors.lines().collect::<Vec<_>>(),
+        ["contractName,function,selector,stateMutability,payable", "Token,,,,", ",mint(),0x1249c58b,,false"]
+    );
+}
+
+#[test]
+fn output_folder_is_the_contracts_folder() {
+    let root = project("same");
+    std::fs::create_dir_all(root.join("generated")).unwrap();
+    let output = run(&root, &["generated", "generated"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is the contracts' folder"));
+}
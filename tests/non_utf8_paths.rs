@@ -0,0 +1,37 @@
+//! A contract file whose name isn't valid UTF-8 is skipped, the other ones are still processed.
+
+use std::process::Command;
+
+mod common;
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_file_is_skipped() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let root = common::temp_path("non_utf8");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/Token.sol"), "contract Token {}\n").unwrap();
+    std::fs::create_dir_all(root.join("out/Token.sol")).unwrap();
+    let abi = r#"{ "abi": [{ "type": "function", "name": "mint", "inputs": [] }] }"#;
+    std::fs::write(root.join("out/Token.sol/Token.json"), abi).unwrap();
+    // Some filesystems (e.g. macOS' APFS) refuse such names, nothing to check there
+    let invalid = root.join("src").join(OsStr::from_bytes(b"Bad\xff.sol"));
+    if std::fs::write(&invalid, "contract Bad {}\n").is_err() {
+        std::fs::remove_dir_all(&root).unwrap();
+        return;
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(&root)
+        .args(["--no-compile", "src", "-"])
+        .output()
+        .expect("the binary runs");
+    std::fs::remove_dir_all(&root).unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("isn't valid UTF-8"), "{}", stderr);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("mint()"));
+}
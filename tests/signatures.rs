@@ -0,0 +1,36 @@
+//! Signatures written by hand, as given to the `sig` subcommand.
+
+use selector_calculator::{canonical_signature, function_selector, SelectorError};
+
+#[test]
+fn names_spaces_and_aliases_are_dropped() {
+    assert_eq!(canonical_signature("transfer(address to, uint amount)").unwrap(), "transfer(address,uint256)");
+    assert_eq!(canonical_signature(" totalSupply() ").unwrap(), "totalSupply()");
+    assert_eq!(
+        canonical_signature("fill((address,(uint256,bytes32)[]) calldata order)").unwrap(),
+        "fill((address,(uint256,bytes32)[]))"
+    );
+    assert_eq!(canonical_signature("g((uint,int)[2][] memory xs, bytes)").unwrap(), "g((uint256,int256)[2][],bytes)");
+    assert_eq!(canonical_signature("approve(address payable,uint256)").unwrap(), "approve(address,uint256)");
+}
+
+#[test]
+fn selector_of_a_canonicalized_signature() {
+    let signature = canonical_signature("approve(address spender, uint value)").unwrap();
+    assert_eq!(hex::encode(function_selector(&signature)), "095ea7b3");
+}
+
+#[test]
+fn malformed_signatures() {
+    for signature in ["f(uint256", "f(uint256))", "f((uint256,address)", "f)uint256(", "(uint256)", "f", "f(,uint256)"] {
+        assert!(canonical_signature(signature).is_err(), "'{}' should be rejected", signature);
+    }
+}
+
+#[test]
+fn types_outside_the_abi_are_typed_errors() {
+    assert_eq!(canonical_signature("fill(Order order)"), Err(SelectorError::InvalidType("Order".to_string())));
+    assert_eq!(canonical_signature("f(uint257)"), Err(SelectorError::InvalidType("uint257".to_string())));
+    assert_eq!(canonical_signature("f((uint,bool)[x])"), Err(SelectorError::InvalidType("(uint,bool)[x]".to_string())));
+    assert!(matches!(canonical_signature("f(uint256"), Err(SelectorError::MalformedSignature(_))));
+}
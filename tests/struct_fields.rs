@@ -0,0 +1,30 @@
+//! Dotted names of the structs' fields, for the manifest's decoders.
+
+use selector_calculator::{canonical_type, component_names, AbiInput};
+
+#[test]
+fn two_levels_of_nested_structs() {
+    let json = r#"{ "name": "order", "type": "tuple", "components": [
+        { "name": "maker", "type": "address" },
+        { "name": "fee", "type": "tuple", "components": [
+            { "name": "amount", "type": "uint" },
+            { "name": "tokens", "type": "address[]" }
+        ] }
+    ] }"#;
+    let input: AbiInput = serde_json::from_str(json).unwrap();
+    let fields = component_names(&input);
+    let expected = [("order.maker", "address"), ("order.fee.amount", "uint256"), ("order.fee.tokens", "address[]")];
+    assert_eq!(fields, expected.map(|(name, field_type)| (name.to_string(), field_type.to_string())));
+    // The names never get into the canonical type
+    assert_eq!(canonical_type(&input), "(address,(uint256,address[]))");
+}
+
+#[test]
+fn arrays_of_structs_and_plain_types() {
+    let json = r#"{ "name": "orders", "type": "tuple[2]", "components": [{ "name": "maker", "type": "address" }] }"#;
+    let input: AbiInput = serde_json::from_str(json).unwrap();
+    assert_eq!(component_names(&input), [("orders[2].maker".to_string(), "address".to_string())]);
+
+    let plain: AbiInput = serde_json::from_str(r#"{ "name": "amount", "type": "uint256" }"#).unwrap();
+    assert!(component_names(&plain).is_empty());
+}
@@ -0,0 +1,48 @@
+//! Struct parameters are expanded to their components' types, checked against the `cast sig` values.
+#![cfg(unix)]
+
+use selector_calculator::function_selector;
+
+mod common;
+
+/// `selectors.csv` written for a fixture ABI.
+fn selectors_csv(fixture: &str) -> String {
+    let abi = std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), fixture)).unwrap();
+    let root = common::forge_project("tuples", &[("Structs", &abi)]);
+    let output = common::run_in_project(&root, &["src", "generated"]);
+    let selectors = std::fs::read_to_string(root.join("generated/selectors/selectors.csv"));
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    selectors.unwrap()
+}
+
+#[test]
+fn struct_nested_struct_and_struct_array() {
+    let selectors = selectors_csv("structs.json");
+    for (signature, selector) in [
+        // Uniswap V3's SwapRouter
+        ("exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))", "0x414bf389"),
+        // Multicall3
+        ("aggregate3((address,bool,bytes)[])", "0x82ad56cb"),
+        // Seaport, an order of struct arrays in a struct
+        (
+            "fulfillOrder(((address,address,(uint8,address,uint256,uint256,uint256)[],\
+             (uint8,address,uint256,uint256,uint256,address)[],uint8,uint256,uint256,bytes32,uint256,bytes32,uint256),\
+             bytes),bytes32)",
+            "0xb3a34c4c",
+        ),
+    ] {
+        let row = format!(",\"{}\",{}", signature, selector);
+        assert!(selectors.contains(&row), "{} not in:\n{}", row, selectors);
+    }
+}
+
+#[test]
+fn array_suffixes_are_kept() {
+    let selectors = selectors_csv("arrays.json");
+    assert!(selectors.contains(",foo(uint256[2][]),0x222ceb7c"), "{}", selectors);
+    for signature in ["setPoints((uint256,uint256)[3])", "grid(uint8[][2],(address,bytes32[2])[3][])"] {
+        let row = format!(",\"{}\",0x{}", signature, hex::encode(function_selector(signature)));
+        assert!(selectors.contains(&row), "{} not in:\n{}", row, selectors);
+    }
+}
@@ -0,0 +1,62 @@
+//! `<Contract>.txt` files of `signature -> hash` lines (`--txt`), beside the CSV files.
+#![cfg(unix)]
+
+use std::path::Path;
+
+mod common;
+
+const TOKEN_ABI: &str = r#"[
+    { "type": "function", "name": "transfer", "inputs": [
+        { "name": "to", "type": "address" }, { "name": "value", "type": "uint256" }
+    ] },
+    { "type": "function", "name": "approve", "inputs": [
+        { "name": "spender", "type": "address" }, { "name": "value", "type": "uint256" }
+    ] },
+    { "type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+        { "name": "from", "type": "address", "indexed": true },
+        { "name": "to", "type": "address", "indexed": true },
+        { "name": "value", "type": "uint256", "indexed": false }
+    ] }
+]"#;
+
+fn run(root: &Path) {
+    let output = common::run_in_project(root, &["src", "generated", "-q", "--txt"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn signature_arrow_hash_lines() {
+    let root = common::forge_project("txt", &[("Token", TOKEN_ABI)]);
+    run(&root);
+    let selectors = std::fs::read_to_string(root.join("generated/selectors/Token.txt")).unwrap();
+    let events = std::fs::read_to_string(root.join("generated/events/Token.txt")).unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(
+        selectors.lines().collect::<Vec<_>>(),
+        ["approve(address,uint256) -> 0x095ea7b3", "transfer(address,uint256) -> 0xa9059cbb"]
+    );
+    assert_eq!(
+        events,
+        "Transfer(address,address,uint256) -> 0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef\n"
+    );
+}
+
+/// `a/Token` (told apart from `b/Token`) and `a_Token` have the same file name once the `/` is replaced.
+#[test]
+fn same_file_name_gets_a_suffix() {
+    let pause = r#"[{ "type": "function", "name": "pause", "inputs": [] }]"#;
+    let root = common::forge_project("txt_suffix", &[("a_Token", pause)]);
+    for (folder, function) in [("a", "mint"), ("b", "burn")] {
+        std::fs::create_dir_all(root.join("src").join(folder)).unwrap();
+        std::fs::write(root.join("src").join(folder).join("Token.sol"), "contract Token {}\n").unwrap();
+        let artifacts = root.join("out").join(folder).join("Token.sol");
+        std::fs::create_dir_all(&artifacts).unwrap();
+        let abi = format!(r#"{{ "abi": [{{ "type": "function", "name": "{}", "inputs": [] }}] }}"#, function);
+        std::fs::write(artifacts.join("Token.json"), abi).unwrap();
+    }
+    run(&root);
+    let read = |name: &str| std::fs::read_to_string(root.join("generated/selectors").join(name)).unwrap();
+    let files = [read("a_Token.txt"), read("b_Token.txt"), read("a_Token-2.txt")];
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(files, ["mint() -> 0x1249c58b\n", "burn() -> 0x44df8e70\n", "pause() -> 0x8456cb59\n"]);
+}
@@ -0,0 +1,36 @@
+//! ABIs written by hand or from other tools can use the `uint` and `int` aliases, hashed as `uint256` and `int256`.
+
+use selector_calculator::{function_selector, signature_from_abi, AbiEntry};
+
+fn signature(entry_json: &str) -> String {
+    let entry: AbiEntry = serde_json::from_str(entry_json).unwrap();
+    signature_from_abi(&entry)
+}
+
+#[test]
+fn balance_of_uint() {
+    let signature =
+        signature(r#"{ "type": "function", "name": "balanceOf", "inputs": [{ "name": "id", "type": "uint" }] }"#);
+    assert_eq!(signature, "balanceOf(uint256)");
+    assert_eq!(hex::encode(function_selector(&signature)), "9cc7f708");
+}
+
+#[test]
+fn aliases_in_arrays_and_tuples() {
+    let signature = signature(
+        r#"{
+            "type": "function",
+            "name": "settle",
+            "inputs": [
+                { "name": "deltas", "type": "int[]" },
+                {
+                    "name": "fees",
+                    "type": "tuple[]",
+                    "components": [{ "name": "amount", "type": "uint" }, { "name": "bps", "type": "uint16" }]
+                },
+                { "name": "total", "type": "uint256" }
+            ]
+        }"#,
+    );
+    assert_eq!(signature, "settle(int256[],(uint256,uint16)[],uint256)");
+}
@@ -0,0 +1,63 @@
+//! Files under the vendored folders (e.g. `lib/`) aren't walked by default.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+/// A forge project in a temporary folder, with compiled artifacts for `src/Token.sol` and a dependency's
+/// `lib/dep/src/Dep.sol`, so it can be run with `--no-compile`.
+fn project(name: &str) -> PathBuf {
+    let root = common::temp_path(name);
+    let _ = std::fs::remove_dir_all(&root);
+    for (source, contract, function) in [("src/Token.sol", "Token", "mint"), ("lib/dep/src/Dep.sol", "Dep", "burn")] {
+        let source_path = root.join(source);
+        std::fs::create_dir_all(source_path.parent().unwrap()).unwrap();
+        std::fs::write(&source_path, format!("contract {} {{}}\n", contract)).unwrap();
+        let artifacts = root.join("out").join(Path::new(source).file_name().unwrap());
+        std::fs::create_dir_all(&artifacts).unwrap();
+        let abi = format!(r#"{{ "abi": [{{ "type": "function", "name": "{}", "inputs": [] }}] }}"#, function);
+        std::fs::write(artifacts.join(format!("{}.json", contract)), abi).unwrap();
+    }
+    root
+}
+
+/// Run the binary on the whole project, printing the selectors to the standard output.
+fn run(root: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_selector_calculator"))
+        .current_dir(root)
+        .args([".", "-", "-q", "--no-compile", "--only", "functions"])
+        .args(args)
+        .output()
+        .expect("the binary runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn vendored_file_is_skipped() {
+    let root = project("skipped");
+    let stdout = run(&root, &[]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(stdout.contains("mint()"), "{}", stdout);
+    assert!(!stdout.contains("burn()"), "{}", stdout);
+}
+
+#[test]
+fn vendored_file_is_walked_without_default_excludes() {
+    let root = project("walked");
+    let stdout = run(&root, &["--no-default-excludes"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(stdout.contains("mint()"), "{}", stdout);
+    assert!(stdout.contains("burn()"), "{}", stdout);
+}
+
+#[test]
+fn max_depth_limits_the_walk() {
+    let root = project("depth");
+    let shallow = run(&root, &["--max-depth", "1"]);
+    let deep = run(&root, &["--max-depth", "2"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(!shallow.contains("mint()"), "{}", shallow);
+    assert!(deep.contains("mint()"), "{}", deep);
+}